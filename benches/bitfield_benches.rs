@@ -1,62 +1,214 @@
 ///////////////////////////////////////////////////////////////////////////////
-extern crate bits;
-extern crate test;
-
 use bits::bitfield::BitField;
+use std::cmp;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
 
 
 ///////////////////////////////////////////////////////////////////////////////
-#[bench]
-fn bench_insert(b: &mut test::Bencher)
+fn bench_insert(c: &mut Criterion)
 {
     let mut bf = BitField::with_capacity(4);
-    b.iter(|| assert!(bf.insert_u64(0xa5a5a5a5, 0, 31).is_ok()));
+    c.bench_function("insert", |b| b.iter(||
+        assert!(bf.insert_u64(black_box(0xa5a5a5a5), 0, 31).is_ok())));
     assert!(bf.as_slice() == [0xa5, 0xa5, 0xa5, 0xa5]);
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-#[bench]
-fn bench_insert_non_byte_boundaries(b: &mut test::Bencher)
+fn bench_insert_non_byte_boundaries(c: &mut Criterion)
 {
     let mut bf = BitField::with_capacity(4);
-    b.iter(|| assert!(bf.insert_u64(0xa5a5a5a5, 5, 27).is_ok()));
+    c.bench_function("insert_non_byte_boundaries", |b| b.iter(||
+        assert!(bf.insert_u64(black_box(0xa5a5a5a5), 5, 27).is_ok())));
     assert!(bf.as_slice() == [0x02, 0x5a, 0x5a, 0x50]);
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-#[bench]
-fn bench_retrieve(b: &mut test::Bencher)
+fn bench_retrieve(c: &mut Criterion)
 {
     let mut bf = BitField::with_capacity(4);
     assert!(bf.insert_u64(0xa5a5a5a5, 0, 31).is_ok());
-    assert!(bf.insert_u64(0xa5a5a5a5, 0, 31).is_ok());
-    b.iter(|| assert!(bf.retrieve_u64(0, 31).unwrap() == 0xa5a5a5a5));
+    c.bench_function("retrieve", |b| b.iter(||
+        assert!(black_box(&bf).retrieve_u64(0, 31).unwrap() == 0xa5a5a5a5)));
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-#[bench]
-fn bench_retrieve_non_byte_boundaries(b: &mut test::Bencher)
+fn bench_retrieve_non_byte_boundaries(c: &mut Criterion)
 {
-    let mut bf = BitField::with_capacity(4);        
+    let mut bf = BitField::with_capacity(4);
     assert!(bf.insert_u64(0xa5a5a5a5, 0, 31).is_ok());
-    b.iter(|| assert!(bf.retrieve_u64(5, 27).unwrap() == 0x005a5a5a));
+    c.bench_function("retrieve_non_byte_boundaries", |b| b.iter(||
+        assert!(black_box(&bf).retrieve_u64(5, 27).unwrap() == 0x005a5a5a)));
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-#[bench]
-fn bench_right_shift(b: &mut test::Bencher)
+fn bench_right_shift(c: &mut Criterion)
 {
     let mut bf = BitField::with_capacity(4);
     assert!(bf.insert_u64(0xa5a5a5a5, 0, 31).is_ok());
-    b.iter(|| assert!((bf >> 7).as_slice() == [0x01, 0x4b, 0x4b, 0x4b]));
+    c.bench_function("right_shift", |b| b.iter(||
+        assert!((black_box(&bf) >> 7).as_slice() == [0x01, 0x4b, 0x4b, 0x4b])));
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-#[bench]
-fn bench_left_shift(b: &mut test::Bencher)
+fn bench_left_shift(c: &mut Criterion)
 {
     let mut bf = BitField::with_capacity(4);
     assert!(bf.insert_u64(0xa5a5a5a5, 0, 31).is_ok());
-    b.iter(|| assert!((bf << 7).as_slice() == [0xd2, 0xd2, 0xd2, 0x80]));
+    c.bench_function("left_shift", |b| b.iter(||
+        assert!((black_box(&bf) << 7).as_slice() == [0xd2, 0xd2, 0xd2, 0x80])));
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// A packet sized pair of objects for comparing the allocating operators with
+// their compound assignment forms.
+fn packets() -> (BitField, BitField)
+{
+    let a: Vec<u8> = (0..1500).map(|i| (i * 7) as u8).collect();
+    let b: Vec<u8> = (0..1500).map(|i| (i * 13) as u8).collect();
+
+    (BitField::from_slice(&a), BitField::from_slice(&b))
+}
+
+///////////////////////////////////////////////////////////////////////////////
+fn bench_and(c: &mut Criterion)
+{
+    let (mut a, b) = packets();
+    let mut group = c.benchmark_group("and");
+    group.bench_function("allocating", |bench| bench.iter(|| black_box(&a) & &b));
+    group.bench_function("assign", |bench| bench.iter(|| a &= black_box(&b)));
+    group.finish();
+}
+
+///////////////////////////////////////////////////////////////////////////////
+fn bench_or(c: &mut Criterion)
+{
+    let (mut a, b) = packets();
+    let mut group = c.benchmark_group("or");
+    group.bench_function("allocating", |bench| bench.iter(|| black_box(&a) | &b));
+    group.bench_function("assign", |bench| bench.iter(|| a |= black_box(&b)));
+    group.finish();
+}
+
+///////////////////////////////////////////////////////////////////////////////
+fn bench_xor(c: &mut Criterion)
+{
+    let (mut a, b) = packets();
+    let mut group = c.benchmark_group("xor");
+    group.bench_function("allocating", |bench| bench.iter(|| black_box(&a) ^ &b));
+    group.bench_function("assign", |bench| bench.iter(|| a ^= black_box(&b)));
+    group.finish();
+}
+
+///////////////////////////////////////////////////////////////////////////////
+fn bench_not(c: &mut Criterion)
+{
+    let (mut a, _) = packets();
+    let mut group = c.benchmark_group("not");
+    group.bench_function("allocating", |bench| bench.iter(|| !black_box(&a)));
+    group.bench_function("invert", |bench| bench.iter(|| black_box(&mut a).invert()));
+    group.finish();
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Bytes from a fixed seed, so every run shifts the same data and a shift in
+// place never works on bits already shifted out.
+fn random_bytes(len: usize) -> Vec<u8>
+{
+    let mut state = 0x2545f4914f6cdd1du64;
+
+    (0..len).map(|_|
+    {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state as u8
+    }).collect()
 }
 
+///////////////////////////////////////////////////////////////////////////////
+fn bench_shift_assign(c: &mut Criterion)
+{
+    let a = BitField::from_slice(&random_bytes(1500));
+    let mut group = c.benchmark_group("shift_packet");
+    group.bench_function("shl_allocating", |bench| bench.iter(|| black_box(&a) << 7));
+    group.bench_function("shl_assign", |bench| bench.iter_batched(|| a.clone(),
+        |mut a| { a <<= black_box(7); a }, BatchSize::SmallInput));
+    group.bench_function("shr_allocating", |bench| bench.iter(|| black_box(&a) >> 7));
+    group.bench_function("shr_assign", |bench| bench.iter_batched(|| a.clone(),
+        |mut a| { a >>= black_box(7); a }, BatchSize::SmallInput));
+    group.finish();
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// The original shift, moving one byte at a time through the public interface,
+// kept as a baseline for the word at a time implementation.  MSB0 only.
+fn bytewise_shr(bf: &mut BitField, shift: usize)
+{
+    let bits = bf.bit_len();
+    let mut end = bits;
+
+    while end > shift
+    {
+        let start = end - cmp::min(8, end - shift);
+        let value = bf.retrieve_u64(start - shift, end - shift - 1).unwrap();
+        bf.insert_u64(value, start, end - 1).unwrap();
+        end = start;
+    }
+
+    if bits > 0
+    {
+        bf.clear_range(0, cmp::min(shift, bits) - 1).unwrap();
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+fn bench_shift_large(c: &mut Criterion)
+{
+    let bytes = random_bytes(4096);
+    let bf = BitField::from_slice(&bytes);
+    let mut group = c.benchmark_group("shift_4k");
+    group.bench_function("bytewise", |bench| bench.iter_batched(|| bf.clone(),
+        |mut bf| { bytewise_shr(&mut bf, black_box(13)); bf }, BatchSize::SmallInput));
+    group.bench_function("wordwise", |bench| bench.iter_batched(|| bf.clone(),
+        |mut bf| { bf >>= black_box(13); bf }, BatchSize::SmallInput));
+    group.finish();
+
+    let mut reference = BitField::from_slice(&bytes);
+    let mut shifted = reference.clone();
+    bytewise_shr(&mut reference, 13);
+    shifted >>= 13;
+    assert!(reference == shifted);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+fn bench_find_pattern(c: &mut Criterion)
+{
+    let mut capture: Vec<u8> = (0..65536).map(|i| (i * 7) as u8).collect();
+    capture[60000..60004].copy_from_slice(&[0x1a, 0xcf, 0xfc, 0x1d]);
+
+    let bf = BitField::from_slice(&capture) << 3;
+    let marker = BitField::from_slice(&[0x1a, 0xcf, 0xfc, 0x1d]);
+    let mut group = c.benchmark_group("find_pattern_64k");
+    group.bench_function("exact", |bench| bench.iter(||
+        assert!(black_box(&bf).find_pattern(&marker) == Some(479997))));
+    group.bench_function("within_3", |bench| bench.iter(||
+        black_box(&bf).find_pattern_within(&marker, 3)));
+    group.finish();
+}
+
+///////////////////////////////////////////////////////////////////////////////
+criterion_group!(benches,
+                 bench_insert,
+                 bench_insert_non_byte_boundaries,
+                 bench_retrieve,
+                 bench_retrieve_non_byte_boundaries,
+                 bench_right_shift,
+                 bench_left_shift,
+                 bench_and,
+                 bench_or,
+                 bench_xor,
+                 bench_not,
+                 bench_shift_assign,
+                 bench_shift_large,
+                 bench_find_pattern);
+criterion_main!(benches);
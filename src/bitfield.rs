@@ -1,478 +1,1274 @@
-use std::{cmp, fmt, uint, u64, u32, u16, u8};
+use core::{cmp, fmt};
+use core::hash::{Hash, Hasher};
+use core::ops::RangeBounds;
+use core::ops::{Index, IndexMut, Not, Shl, Shr, ShlAssign, ShrAssign};
+#[cfg(feature = "alloc")]
+use core::ops::{BitAnd, BitOr, BitXor, BitAndAssign, BitOrAssign, BitXorAssign};
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, string::ToString, vec, vec::Vec};
+#[cfg(feature = "std")]
+use std::io;
+
+use crate::bitslice::{bit_slice_reads, bit_slice_writes};
+use crate::bitslice::{BitIndices, BitSlice, BitSliceMut, Chunks, Iter, Windows};
+use crate::hexdump::HexDump;
+use crate::storage::BitStorage;
 
 ///////////////////////////////////////////////////////////////////////////////
-#[deriving(Show)]
+/// The reasons an operation on a BitField or view can fail, with the bit
+/// numbers and widths involved.  Ranges are given by their first and last
+/// bits, as passed to the operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum BitFieldError
 {
-    InternalError,
-    InvalidIndex,
-    NegativeRange,
-    ExceededDataRange,
+    /// Part of the range lies beyond the last bit of an object of
+    /// <b>bit_len</b> bits.  A single bit has <b>start_bit</b> equal to
+    /// <b>stop_bit</b>.
+    InvalidIndex{start_bit: usize, stop_bit: usize, bit_len: usize},
+    /// The range stops before it starts.
+    NegativeRange{start_bit: usize, stop_bit: usize},
+    /// The range ends before bit 0, as <b>start_bit..0</b> does.
+    EmptyRange{start_bit: usize},
+    /// The range is <b>width</b> bits but at most <b>max_width</b> can be
+    /// read or written.
+    ExceededDataRange{width: usize, max_width: usize},
+    /// The value can not be represented in a range of <b>width</b> bits.
+    Overflow{width: usize},
+    /// The operands of <b>Alignment::Strict</b> differ in length.
+    LengthMismatch{left_bits: usize, right_bits: usize},
+    /// Growing to <b>bits</b> bits needs more than the
+    /// <b>capacity_bits</b> bits of fixed size storage.
+    CapacityExceeded{bits: usize, capacity_bits: usize},
+    /// Text being parsed does not follow its format, first at the
+    /// character at <b>line</b> and <b>column</b>, both counted from 1.
+    /// Text that ends too soon is reported just past its end.
+    Malformed{line: usize, column: usize},
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-#[deriving(Clone,PartialEq)]
-pub struct BitField
+impl fmt::Display for BitFieldError
 {
-    bytes: Vec<u8>
+    ///////////////////////////////////////////////////////////////////////////
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result
+    {
+        match *self
+        {
+            BitFieldError::InvalidIndex{start_bit, stop_bit, bit_len} if start_bit == stop_bit =>
+                write!(fmt, "bit {} is outside an object of {} bits", start_bit, bit_len),
+            BitFieldError::InvalidIndex{start_bit, stop_bit, bit_len} =>
+                write!(fmt, "bits {} to {} are outside an object of {} bits",
+                       start_bit, stop_bit, bit_len),
+            BitFieldError::NegativeRange{start_bit, stop_bit} =>
+                write!(fmt, "range stops at bit {} before it starts at bit {}",
+                       stop_bit, start_bit),
+            BitFieldError::EmptyRange{start_bit} =>
+                write!(fmt, "range starting at bit {} ends before bit 0", start_bit),
+            BitFieldError::ExceededDataRange{width, max_width} =>
+                write!(fmt, "range of {} bits is wider than the {} bits allowed",
+                       width, max_width),
+            BitFieldError::Overflow{width} =>
+                write!(fmt, "value does not fit in {} bits", width),
+            BitFieldError::LengthMismatch{left_bits, right_bits} =>
+                write!(fmt, "lengths of {} and {} bits differ", left_bits, right_bits),
+            BitFieldError::CapacityExceeded{bits, capacity_bits} =>
+                write!(fmt, "storage of {} bits can not hold {} bits", capacity_bits, bits),
+            BitFieldError::Malformed{line, column} =>
+                write!(fmt, "text is malformed at line {}, column {}", line, column),
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[cfg(feature = "std")]
+impl std::error::Error for BitFieldError {}
+
+///////////////////////////////////////////////////////////////////////////////
+/// Lets decoders reading from an <b>io::Read</b> use <b>?</b> on both.
+#[cfg(feature = "std")]
+impl From<BitFieldError> for io::Error
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn from(error: BitFieldError) -> io::Error
+    {
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// How the bitwise operations line up two BitField Objects of different
+/// lengths.  The shorter operand is extended with zero bits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Alignment
+{
+    /// The most significant ends are aligned, which is bit 0 for
+    /// <b>BitOrder::Msb0</b>.  Suits MSB-first frames.
+    Left,
+    /// The least significant ends are aligned, as when combining numbers.
+    /// This is what the <b>&amp;</b>, <b>|</b> and <b>^</b> operators use.
+    Right,
+    /// The lengths must match, otherwise
+    /// <b>BitFieldError::LengthMismatch</b> is returned.
+    Strict,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// The representation used for signed values stored in a BitField.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignedEncoding
+{
+    /// Negative values are stored as the two's complement of the magnitude.
+    TwosComplement,
+    /// Negative values are stored as the bitwise inverse of the magnitude.
+    OnesComplement,
+    /// The most significant bit of the range is the sign and the rest the
+    /// magnitude.
+    SignMagnitude,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// How a fixed-point value that falls between two representable values is
+/// rounded when it is inserted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding
+{
+    /// Round to the nearest value, with halfway cases away from zero.
+    Nearest,
+    /// Round to the nearest value, with halfway cases to the even value.
+    NearestEven,
+    /// Round towards negative infinity.
+    Floor,
+    /// Round towards positive infinity.
+    Ceil,
+    /// Round towards zero.
+    TowardZero,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// What an insert does with a value its range can not represent.
+///
+/// <p>Unless an object or view is given a policy, each insert keeps its own:
+/// unsigned inserts truncate, signed inserts are checked and fixed-point
+/// inserts saturate.</p>
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OverflowPolicy
+{
+    /// Keep the low bits of the value, so 6 in a 2 bit range becomes 2.
+    /// Signed values keep their sign and the low bits of their magnitude,
+    /// or for two's complement the low bits of the value.
+    Truncate,
+    /// Return <b>BitFieldError::Overflow</b>, leaving the range unchanged.
+    Checked,
+    /// Store the nearest value the range can represent.
+    Saturate,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl OverflowPolicy
+{
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns <b>value</b> as the unsigned value to store in <b>width</b>
+    /// bits.  The value is wider than the range so negative values, as
+    /// fixed-point ones can be, are handled too.
+    pub(crate) fn fit_unsigned(self, value: i128, width: usize) -> Result<u64, BitFieldError>
+    {
+        let max = u64::MAX >> (u64::BITS as usize - width);
+
+        if (0..=max as i128).contains(&value)
+        {
+            return Ok(value as u64);
+        }
+
+        match self
+        {
+            OverflowPolicy::Truncate => Ok(value as u64 & max),
+            OverflowPolicy::Checked => Err(BitFieldError::Overflow{width}),
+            OverflowPolicy::Saturate => Ok(value.clamp(0, max as i128) as u64),
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns <b>value</b> as a value that <b>width</b> bits can hold in
+    /// the given <b>encoding</b>.
+    pub(crate) fn fit_signed(self, value: i128, width: usize, encoding: SignedEncoding) ->
+                                                             Result<i64, BitFieldError>
+    {
+        let (min, max) = signed_limits(width, encoding);
+
+        if (min..=max).contains(&value)
+        {
+            return Ok(value as i64);
+        }
+
+        match (self, encoding)
+        {
+            (OverflowPolicy::Truncate, SignedEncoding::TwosComplement) =>
+            {
+                let shift = i128::BITS as usize - width;
+
+                Ok(((value << shift) >> shift) as i64)
+            }
+            (OverflowPolicy::Truncate, _) =>
+            {
+                let magnitude = (value.unsigned_abs() & ((1u128 << (width - 1)) - 1)) as i64;
+
+                Ok(if value < 0 { -magnitude } else { magnitude })
+            }
+            (OverflowPolicy::Checked, _) => Err(BitFieldError::Overflow{width}),
+            (OverflowPolicy::Saturate, _) => Ok(value.clamp(min, max) as i64),
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// Describes how a fixed-point (scaled integer) range maps to a real value.
+///
+/// <p>The real value is <b>raw</b> * <b>scale</b> + <b>offset</b>, where
+/// <b>raw</b> is the integer stored in the range, two's complement when
+/// <b>signed</b> is set.  For example, a Q3.12 value is
+/// <b>FixedPoint::q(12)</b> in a 16 bit range, and a temperature with a
+/// 0.5 degree LSB starting at -40 degrees is
+/// <b>FixedPoint::new(0.5, -40.0, false)</b>.</p>
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FixedPoint
+{
+    pub scale: f64,
+    pub offset: f64,
+    pub signed: bool,
+    pub rounding: Rounding,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl FixedPoint
+{
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn new(scale: f64, offset: f64, signed: bool) -> FixedPoint
+    {
+        FixedPoint{scale, offset, signed, rounding: Rounding::Nearest}
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// A signed Q format value with <b>fraction_bits</b> bits after the
+    /// binary point.  The integer bits are whatever remains of the range.
+    pub fn q(fraction_bits: u32) -> FixedPoint
+    {
+        FixedPoint::new(1.0 / (1u64 << fraction_bits) as f64, 0.0, true)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn with_rounding(mut self, rounding: Rounding) -> FixedPoint
+    {
+        self.rounding = rounding;
+        self
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// The order in which the bytes of a multi-byte value are stored.
+///
+/// <p>Bytes are counted in 8 bit steps from the start of the range, so a
+/// range need not be byte aligned.  When the width is not a multiple of 8
+/// the most significant byte holds the remaining bits.</p>
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteOrder
+{
+    /// The most significant byte is first, 0x0a0b0c0d is 0a 0b 0c 0d.
+    BigEndian,
+    /// The least significant byte is first, 0x0a0b0c0d is 0d 0c 0b 0a.
+    LittleEndian,
+    /// 16 bit little-endian words with the most significant word first, as
+    /// on the PDP-11, 0x0a0b0c0d is 0b 0a 0d 0c.  An odd byte count places
+    /// the most significant byte alone in the first word.
+    Pdp,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// The convention used to number the bits of a BitField.
+///
+/// <p>Every convention numbers bits 8n through 8n + 7 within a single byte,
+/// they differ in which byte that is, which end of the byte bit 8n is, and
+/// therefore which end of a multi-bit range holds its most significant
+/// bit.</p>
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BitOrder
+{
+    /// Bit 0 is the most significant bit of the first byte.  A range holds
+    /// its most significant bit at <b>start_bit</b>.
+    #[default]
+    Msb0,
+    /// Bit 0 is the least significant bit of the last byte, as if the whole
+    /// object were one big-endian number.  A range holds its least
+    /// significant bit at <b>start_bit</b>.
+    Lsb0,
+    /// The object is a sequence of little-endian words of the given number
+    /// of bytes, and bit 0 is the least significant bit of the first word.
+    /// A range holds its least significant bit at <b>start_bit</b>.
+    Lsb0Words(usize),
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// Returns the smallest and largest values a range of <b>width</b> bits
+/// can hold in the given <b>encoding</b>.
+fn signed_limits(width: usize, encoding: SignedEncoding) -> (i128, i128)
+{
+    let max = (1i128 << (width - 1)) - 1;
+
+    match encoding
+    {
+        SignedEncoding::TwosComplement => (-max - 1, max),
+        _ => (-max, max)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// A type that can be stored in, and read back from, a range of bits.
+///
+/// <p>Unsigned types are truncated to the range when stored and zero
+/// extended when read.  Signed types are stored two's complement, must fit
+/// the range, and are sign extended when read.  An <b>OverflowPolicy</b>
+/// replaces the default handling of values that do not fit.</p>
+pub trait BitStore: Copy
+{
+    /// The widest range the type can be stored in.
+    const BITS: usize;
+
+    /// How values that do not fit the range are stored when no policy is
+    /// given.
+    const OVERFLOW: OverflowPolicy;
+
+    /// Converts the value to the raw bits stored in a range of
+    /// <b>width</b> bits, fitting it as <b>overflow</b> directs.
+    fn to_raw(self, width: usize, overflow: OverflowPolicy) -> Result<u64, BitFieldError>;
+
+    /// Converts the raw bits read from a range of <b>width</b> bits back to
+    /// the value.
+    fn from_raw(raw: u64, width: usize) -> Self;
 }
 
 ///////////////////////////////////////////////////////////////////////////////
+macro_rules! unsigned_bit_store
+{
+    ($($t:ty),*) =>
+    {
+        $(
+            impl BitStore for $t
+            {
+                const BITS: usize = <$t>::BITS as usize;
+                const OVERFLOW: OverflowPolicy = OverflowPolicy::Truncate;
+
+                fn to_raw(self, width: usize, overflow: OverflowPolicy) ->
+                                                     Result<u64, BitFieldError>
+                {
+                    overflow.fit_unsigned(self as i128, width)
+                }
+
+                fn from_raw(raw: u64, _width: usize) -> $t
+                {
+                    raw as $t
+                }
+            }
+        )*
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+macro_rules! signed_bit_store
+{
+    ($($t:ty),*) =>
+    {
+        $(
+            impl BitStore for $t
+            {
+                const BITS: usize = <$t>::BITS as usize;
+                const OVERFLOW: OverflowPolicy = OverflowPolicy::Checked;
+
+                fn to_raw(self, width: usize, overflow: OverflowPolicy) ->
+                                                     Result<u64, BitFieldError>
+                {
+                    overflow.fit_signed(self as i128, width, SignedEncoding::TwosComplement)
+                            .map(|value| value as u64)
+                }
+
+                fn from_raw(raw: u64, width: usize) -> $t
+                {
+                    let shift = u64::BITS as usize - width;
+
+                    (((raw << shift) as i64) >> shift) as $t
+                }
+            }
+        )*
+    }
+}
+
+unsigned_bit_store!(u8, u16, u32, u64, usize);
+signed_bit_store!(i8, i16, i32, i64, isize);
+
+///////////////////////////////////////////////////////////////////////////////
+impl BitStore for bool
+{
+    const BITS: usize = 1;
+    const OVERFLOW: OverflowPolicy = OverflowPolicy::Truncate;
+
+    fn to_raw(self, _width: usize, _overflow: OverflowPolicy) -> Result<u64, BitFieldError>
+    {
+        Ok(self as u64)
+    }
+
+    fn from_raw(raw: u64, _width: usize) -> bool
+    {
+        raw != 0
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// A sequence of bits stored in bytes.
+///
+/// <p>The object holds exactly <b>bit_len()</b> bits.  When that is not a
+/// multiple of 8 the unused bits of the last byte are padding, they are kept
+/// zero and are never addressable.</p>
+///
+/// <p>The bytes are held in a <b>BitStorage</b>, a growable
+/// <b>Vec&lt;u8&gt;</b> unless another is given.  Fixed size storage such as
+/// <b>[u8; N]</b>, <b>[u32; N]</b> or a borrowed <b>&amp;mut [u8]</b> limits
+/// how long the object can grow.</p>
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub struct BitField<S = Vec<u8>>
+{
+    storage: S,
+    bits: usize,
+    order: BitOrder,
+    overflow: Option<OverflowPolicy>
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// A sequence of bits stored in a <b>BitStorage</b>.  Without the
+/// <b>alloc</b> feature there is no default storage.
+#[cfg(not(feature = "alloc"))]
+#[derive(Clone)]
+pub struct BitField<S>
+{
+    storage: S,
+    bits: usize,
+    order: BitOrder,
+    overflow: Option<OverflowPolicy>
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[cfg(feature = "alloc")]
 impl BitField
 {
     ///////////////////////////////////////////////////////////////////////////
     pub fn new() -> BitField
     {
-        BitField{bytes: Vec::new()}
+        BitField{storage: Vec::new(), bits: 0, order: BitOrder::Msb0, overflow: None}
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Creates an object of <b>capacity</b> zeroed bytes.
+    pub fn with_capacity(capacity: usize) -> BitField
+    {
+        BitField::with_bit_capacity(capacity * 8)
     }
 
     ///////////////////////////////////////////////////////////////////////////
-    pub fn with_capacity(capacity: uint) -> BitField
+    /// Creates an object of exactly <b>bits</b> zeroed bits.
+    pub fn with_bit_capacity(bits: usize) -> BitField
     {
-        BitField{bytes: Vec::from_elem(capacity, 0)}
+        BitField{storage: vec![0; bits.div_ceil(8)], bits, order: BitOrder::Msb0, overflow: None}
     }
 
     ///////////////////////////////////////////////////////////////////////////
     pub fn from_slice(values: &[u8]) -> BitField
     {
-        let mut bitfield = BitField::new();
-        bitfield.bytes.push_all(values);
-        bitfield
+        BitField{storage: values.to_vec(), bits: values.len() * 8, order: BitOrder::Msb0,
+                 overflow: None}
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl<S: BitStorage> BitField<S>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    /// Creates an MSB0 object of every bit of <b>storage</b>, which keeps
+    /// its contents.
+    pub fn from_storage(storage: S) -> BitField<S>
+    {
+        let bits = storage.as_bytes().len() * 8;
+
+        BitField{storage, bits, order: BitOrder::Msb0, overflow: None}
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the storage, which may hold bytes beyond <b>len()</b>.
+    pub fn into_storage(self) -> S
+    {
+        self.storage
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the object using the given bit numbering <b>order</b>.
+    ///
+    /// <p>The bytes are not changed, only how bit numbers map onto them.</p>
+    pub fn with_bit_order(mut self, order: BitOrder) -> BitField<S>
+    {
+        self.set_bit_order(order);
+        self
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Changes how bit numbers map onto the bytes.  If the length is not a
+    /// multiple of 8 any bits that become padding under the new order are
+    /// cleared.
+    pub fn set_bit_order(&mut self, order: BitOrder)
+    {
+        if let BitOrder::Lsb0Words(0) = order
+        {
+            panic!("Word width must be at least one byte");
+        }
+
+        self.order = order;
+        self.clear_padding();
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn bit_order(&self) -> BitOrder
+    {
+        self.order
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the object with every insert that is not given a policy
+    /// handling values that do not fit as <b>overflow</b> directs.
+    pub fn with_overflow_policy(mut self, overflow: OverflowPolicy) -> BitField<S>
+    {
+        self.set_overflow_policy(overflow);
+        self
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// See <b>with_overflow_policy</b>.
+    pub fn set_overflow_policy(&mut self, overflow: OverflowPolicy)
+    {
+        self.overflow = Some(overflow);
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the policy set for the object, if any.
+    pub fn overflow_policy(&self) -> Option<OverflowPolicy>
+    {
+        self.overflow
     }
 
     ///////////////////////////////////////////////////////////////////////////
     pub fn clear(&mut self)
     {
-        self.bytes.clear()
+        self.storage.set_byte_len(0);
+        self.bits = 0;
     }
 
     ///////////////////////////////////////////////////////////////////////////
-    pub fn len(&self) -> uint
+    /// Returns the number of bytes used to store the object.  See
+    /// <b>bit_len</b> for the number of bits.
+    pub fn len(&self) -> usize
     {
-        self.bytes.len()
+        self.bits.div_ceil(8)
     }
 
     ///////////////////////////////////////////////////////////////////////////
-    pub fn is_empty(&self) -> bool
+    pub fn bit_len(&self) -> usize
     {
-        self.bytes.is_empty()
+        self.bits
     }
 
     ///////////////////////////////////////////////////////////////////////////
-    pub fn grow(&mut self, size: uint)
+    pub fn is_empty(&self) -> bool
     {
-        self.bytes.grow(size, 0u8);
+        self.bits == 0
     }
 
     ///////////////////////////////////////////////////////////////////////////
-    fn get_mask(bits: uint) -> u8
+    /// Appends <b>size</b> zeroed bytes after the highest numbered bit of the
+    /// object.
+    pub fn grow(&mut self, size: usize)
     {
-        ((1u32 << bits) - 1u32) as u8
+        self.resize_bits(self.bits + size * 8);
     }
 
-    /*
-    pub fn insert<T:AsSlice<u8>>(&mut self,
-                                 value: T,
-                                 start_bit:
-                                 uint,
-                                 stop_bit: uint) ->
-        Result<(), &'static str>
+    ///////////////////////////////////////////////////////////////////////////
+    /// Shortens the object to <b>bits</b> bits, dropping the highest
+    /// numbered bits.  Has no effect if the object is already shorter.
+    pub fn truncate_bits(&mut self, bits: usize)
     {
-        // find out which byte range will be affected by the insert
-        let start_byte = start_bit / 8;
-        let stop_byte  = stop_bit / 8;
+        if bits < self.bits
+        {
+            self.resize_bits(bits);
+        }
+    }
 
-        if start_bit > stop_bit
+    ///////////////////////////////////////////////////////////////////////////
+    /// Changes the length of the object to <b>bits</b> bits.  Bits are added
+    /// or removed after the highest numbered bit, and added bits are zero.
+    /// For <b>BitOrder::Lsb0</b> that is the front of the bytes.
+    ///
+    /// <p>Panics if the storage can not hold <b>bits</b> bits, see
+    /// <b>try_resize_bits</b>.</p>
+    pub fn resize_bits(&mut self, bits: usize)
+    {
+        if self.try_resize_bits(bits).is_err()
         {
-            return Err("Bit range can not be negative");
+            panic!("Storage can not hold {} bits", bits);
         }
-        
-        // find the byte length for the data
-        let byte_length = (stop_byte - start_byte) + 1;
-        
-        if self.len() <= stop_byte
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Changes the length of the object as <b>resize_bits</b>, returning
+    /// <b>BitFieldError::CapacityExceeded</b> and leaving the object
+    /// unchanged if the storage can not hold <b>bits</b> bits.
+    pub fn try_resize_bits(&mut self, bits: usize) -> Result<(), BitFieldError>
+    {
+        let old = self.len();
+        let new = bits.div_ceil(8);
+
+        if !self.storage.set_byte_len(cmp::max(old, new))
         {
-            return Err("Bits are out of range for object")
+            let capacity_bits = self.storage.as_bytes().len() * 8;
+
+            return Err(BitFieldError::CapacityExceeded{bits, capacity_bits});
         }
 
-        let data: &[u8] = value.as_slice();
+        let bytes = self.storage.as_bytes_mut();
 
-        if byte_length > data.len()
+        // storage beyond the old length may hold anything, so added bytes
+        // are zeroed rather than assumed zero
+        match self.order
         {
-            return Err("Bit range can not exceed data length");
+            BitOrder::Lsb0 if new > old =>
+            {
+                bytes.copy_within(0..old, new - old);
+                bytes[..new - old].fill(0);
+            }
+            BitOrder::Lsb0 => bytes.copy_within(old - new..old, 0),
+            _ if new > old => bytes[old..new].fill(0),
+            _ => {}
         }
 
-        //for b in 
+        self.storage.set_byte_len(new);
+        self.bits = bits;
+        self.clear_padding();
         Ok(())
- 
     }
-    */
 
     ///////////////////////////////////////////////////////////////////////////
-    /// Inserts the value into the object at the specified bit locations
-    /// 
-    /// <p>The passed in <b>value</b> will be masked against the
-    /// range specified by <b>start_bit</b> and <b>stop_bit</b>.  For example, 
-    /// if <b>value</b> = 6 (binary 0110), and <b>start_bit</b> = 5, and 
-    /// <b>stop_bit</b> = 6, the <b>value</b> will be masked to 2 (binary 10).<p>
-    pub fn insert_u64(&mut self, value: u64, start_bit: uint, stop_bit: uint) ->
-                                                       Result<(), BitFieldError>
+    /// Appends a single bit after the highest numbered bit.
+    pub fn push_bit(&mut self, value: bool)
     {
-        // find out which byte range will be affected by the insert
-        let start_byte = start_bit / 8;
-        let stop_byte  = stop_bit / 8;
+        self.resize_bits(self.bits + 1);
 
-        if start_bit > stop_bit
+        if value
         {
-            return Err(BitFieldError::NegativeRange);
+            self.set_bit(self.bits - 1).expect("Pushed bit is within the object");
         }
-        
-        // find the byte length for the data
-        let byte_length = (stop_byte - start_byte) + 1;
-        
-        if self.len() <= stop_byte
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Appends the low <b>width</b> bits of <b>value</b> after the highest
+    /// numbered bit, growing the object as needed.
+    ///
+    /// <p>The bits are laid out as by <b>insert_u64</b>, so building a frame
+    /// with <b>push_bits</b> and reading it back field by field with
+    /// <b>retrieve_u64</b> gives the same values.  A value wider than
+    /// <b>width</b> is handled by the object's <b>OverflowPolicy</b>.</p>
+    pub fn push_bits(&mut self, value: u64, width: usize) -> Result<(), BitFieldError>
+    {
+        if width > u64::BITS as usize
         {
-            return Err(BitFieldError::InvalidIndex)
+            return Err(BitFieldError::ExceededDataRange{width, max_width: u64::BITS as usize});
         }
 
-        if (stop_bit - start_bit + 1) > u64::BITS
+        if width == 0
         {
-            return Err(BitFieldError::ExceededDataRange);
+            return Ok(());
         }
 
-        // loop through the bytes to change the value of
-        for i in range(0, byte_length)
+        // fitted first, so a value the policy rejects leaves the length alone
+        let overflow = self.overflow.unwrap_or(OverflowPolicy::Truncate);
+        let value = overflow.fit_unsigned(value as i128, width)?;
+
+        let start_bit = self.bits;
+        self.try_resize_bits(start_bit + width)?;
+        self.insert_u64(value, start_bit, start_bit + width - 1)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Appends every bit of <b>other</b>, a BitField or a view, after the
+    /// highest numbered bit.  The bits keep their numbering relative to each
+    /// other whatever the bit orders of the two.
+    pub fn extend_from_bitslice<'b>(&mut self, other: impl Into<BitSlice<'b>>)
+    {
+        let other = other.into();
+        let start_bit = self.bits;
+
+        self.resize_bits(start_bit + other.bit_len());
+        self.copy_bits(start_bit, other, 0, other.bit_len())
+            .expect("Extended bits are within both objects");
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Removes the <b>width</b> highest numbered bits and returns them as read
+    /// by <b>retrieve_u64</b>.
+    pub fn pop_bits(&mut self, width: usize) -> Result<u64, BitFieldError>
+    {
+        if width > u64::BITS as usize
         {
-            // find the start bit for the current byte
-            let mut current_start_bit = 8 * (start_byte + i);
-            if current_start_bit < start_bit
-            {
-                current_start_bit = start_bit;
-            }
-            // find the stop bit for the current byte
-            let end_of_byte_bit = 8*(start_byte + i + 1) - 1;
-            let current_stop_bit =
-                if end_of_byte_bit > stop_bit
-                {
-                    stop_bit
-                }
-                else
-                {
-                    end_of_byte_bit
-                };
-            
-            // Find the current byte
-            let current_byte = start_byte + i;
-            
-            // Number bits in this byte that are going to be changed
-            let bits_in_byte = current_stop_bit - current_start_bit + 1;
-            
-            // Find the number bits to shift
-            let shift_bits = stop_bit - current_stop_bit;
-            
-            // Find mask and value for this byte
-            let mut byte_mask = BitField::get_mask(bits_in_byte);
-            let mut byte_value = (value >> shift_bits) as u8 & byte_mask;
-            
-            // If the current stop bit is not the end of the byte, then the
-            // value needs to be shifted by the difference
-            if i == (byte_length - 1)
-            {
-                let shift = end_of_byte_bit - current_stop_bit;
-                byte_mask = byte_mask << shift;
-                byte_value = byte_value << shift;
-            }
-            
-            // stores the value by clearing the selected portion of the byte
-            // with negation of the mask then inserts the value in the byte.
-            match self.bytes.get_mut(current_byte)
-            {
-                Some(b) => *b = *b & !byte_mask | byte_value,
-                None => panic!("Current byte is out of range")
-            };
+            return Err(BitFieldError::ExceededDataRange{width, max_width: u64::BITS as usize});
         }
 
-        Ok(())
+        if width > self.bits
+        {
+            return Err(BitFieldError::ExceededDataRange{width, max_width: self.bits});
+        }
+
+        if width == 0
+        {
+            return Ok(0);
+        }
+
+        let start_bit = self.bits - width;
+        let value = self.retrieve_u64(start_bit, self.bits - 1)?;
+
+        self.truncate_bits(start_bit);
+        Ok(value)
     }
 
     ///////////////////////////////////////////////////////////////////////////
-    pub fn insert_uint(&mut self, value: uint, start_bit: uint, stop_bit: uint) ->
-                                                       Result<(), BitFieldError>
+    /// Returns the index into the bytes and the mask of the padding bits of
+    /// the last byte, if the length is not a multiple of 8.
+    fn padding(&self) -> Option<(usize, u8)>
     {
-        if (stop_bit - start_bit + 1) > uint::BITS
+        if self.bits.is_multiple_of(8)
         {
-            return Err(BitFieldError::ExceededDataRange);
+            return None;
         }
 
-        self.insert_u64(value as u64, start_bit, stop_bit)
+        let byte = self.bits / 8;
+        let view = self.as_bitslice();
+        let (bits_in_byte, byte_shift, _) =
+            view.byte_shifts(byte, self.bits, 8 * byte + 7);
+
+        Some((view.physical_byte(byte), BitSlice::get_mask(bits_in_byte) << byte_shift))
     }
 
     ///////////////////////////////////////////////////////////////////////////
-    pub fn insert_u32(&mut self, value: u32, start_bit: uint, stop_bit: uint) ->
-                                                       Result<(), BitFieldError>
+    /// Zeroes the bits of the last byte beyond the length of the object.
+    fn clear_padding(&mut self)
     {
-        if (stop_bit - start_bit + 1) > u32::BITS
+        if let Some((byte, mask)) = self.padding()
         {
-            return Err(BitFieldError::ExceededDataRange);
+            self.bytes_mut()[byte] &= !mask;
         }
+    }
 
-        self.insert_u64(value as u64, start_bit, stop_bit)
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn as_slice(&self) -> &[u8]
+    {
+        &self.storage.as_bytes()[..self.len()]
     }
 
     ///////////////////////////////////////////////////////////////////////////
-    pub fn insert_u16(&mut self, value: u16, start_bit: uint, stop_bit: uint) ->
-                                                       Result<(), BitFieldError>
+    fn bytes_mut(&mut self) -> &mut [u8]
+    {
+        let len = self.len();
+
+        &mut self.storage.as_bytes_mut()[..len]
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns a borrowed view of every bit of the object.
+    ///
+    /// <p>The reading and writing methods of the object forward to
+    /// <b>BitSlice</b> and <b>BitSliceMut</b> views like this one, so each
+    /// behaves exactly as it does on a view.  A view borrows the object, so
+    /// it can not be returned by <b>Deref</b>.</p>
+    pub fn as_bitslice(&self) -> BitSlice<'_>
+    {
+        BitSlice::from_parts(self.as_slice(), self.order, 0, self.bits)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns a <b>HexDump</b> of the object, which can be configured
+    /// before it is displayed.
+    pub fn hex_dump(&self) -> HexDump<'_>
+    {
+        HexDump::new(self)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns a mutable borrowed view of every bit of the object.
+    pub fn as_mut_bitslice(&mut self) -> BitSliceMut<'_>
+    {
+        let (order, bits, overflow) = (self.order, self.bits, self.overflow);
+
+        BitSliceMut::from_parts(self.bytes_mut(), order, 0, bits).with_overflow(overflow)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns a mutable view of the bits in <b>range</b>, numbered from 0.
+    pub fn slice_mut<R: RangeBounds<usize>>(&mut self, range: R) ->
+                                             Result<BitSliceMut<'_>, BitFieldError>
+    {
+        let (start_bit, stop_bit) = self.bit_range(range)?;
+        let (order, overflow) = (self.order, self.overflow);
+
+        Ok(BitSliceMut::from_parts(self.bytes_mut(), order, start_bit,
+                                   stop_bit - start_bit + 1).with_overflow(overflow))
+    }
+
+    bit_slice_reads!();
+    bit_slice_writes!();
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Shifts <b>bytes</b>, read as one big-endian number, <b>shift</b> bits
+    /// towards the first byte, filling with zero.
+    ///
+    /// <p>Whole bytes are moved with a single memmove, then any remaining
+    /// bits are shifted 64 at a time, carrying in from the following
+    /// byte.</p>
+    fn shl_be(bytes: &mut [u8], shift: usize)
     {
-        if (stop_bit - start_bit + 1) > u16::BITS
+        let len = bytes.len();
+        let byte_shift = shift / 8;
+        let bit_shift = (shift % 8) as u32;
+
+        if byte_shift >= len
         {
-            return Err(BitFieldError::ExceededDataRange);
+            bytes.fill(0);
+            return;
         }
 
-        self.insert_u64(value as u64, start_bit, stop_bit)
+        bytes.copy_within(byte_shift.., 0);
+        bytes[len - byte_shift..].fill(0);
+
+        if bit_shift == 0
+        {
+            return;
+        }
+
+        // working forwards, the carry byte has not been shifted yet
+        let mut i = 0;
+        while i + 8 <= len
+        {
+            let carry = if i + 8 < len { bytes[i + 8] } else { 0 };
+            let word = u64::from_be_bytes(bytes[i..i + 8].try_into().unwrap());
+            let word = (word << bit_shift) | (carry >> (8 - bit_shift)) as u64;
+
+            bytes[i..i + 8].copy_from_slice(&word.to_be_bytes());
+            i += 8;
+        }
+
+        while i < len
+        {
+            let carry = if i + 1 < len { bytes[i + 1] } else { 0 };
+
+            bytes[i] = (bytes[i] << bit_shift) | (carry >> (8 - bit_shift));
+            i += 1;
+        }
     }
 
     ///////////////////////////////////////////////////////////////////////////
-    pub fn insert_u8(&mut self, value: u16, start_bit: uint, stop_bit: uint) ->
-                                                       Result<(), BitFieldError>
+    /// Shifts <b>bytes</b>, read as one big-endian number, <b>shift</b> bits
+    /// towards the last byte, filling with zero.  See <b>shl_be</b>.
+    fn shr_be(bytes: &mut [u8], shift: usize)
+    {
+        let len = bytes.len();
+        let byte_shift = shift / 8;
+        let bit_shift = (shift % 8) as u32;
+
+        if byte_shift >= len
+        {
+            bytes.fill(0);
+            return;
+        }
+
+        bytes.copy_within(..len - byte_shift, byte_shift);
+        bytes[..byte_shift].fill(0);
+
+        if bit_shift == 0
+        {
+            return;
+        }
+
+        // working backwards, the carry byte has not been shifted yet
+        let mut i = len;
+        while i >= 8
+        {
+            let carry = if i > 8 { bytes[i - 9] } else { 0 };
+            let word = u64::from_be_bytes(bytes[i - 8..i].try_into().unwrap());
+            let word = (word >> bit_shift) | ((carry as u64) << (64 - bit_shift));
+
+            bytes[i - 8..i].copy_from_slice(&word.to_be_bytes());
+            i -= 8;
+        }
+
+        while i > 0
+        {
+            let carry = if i > 1 { bytes[i - 2] } else { 0 };
+
+            bytes[i - 1] = (bytes[i - 1] >> bit_shift) | (carry << (8 - bit_shift));
+            i -= 1;
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Shifts <b>bytes</b>, read as one little-endian number, <b>shift</b>
+    /// bits towards the last (most significant) byte, filling with zero.
+    fn shl_le(bytes: &mut [u8], shift: usize)
+    {
+        let len = bytes.len();
+        let byte_shift = shift / 8;
+        let bit_shift = (shift % 8) as u32;
+
+        if byte_shift >= len
+        {
+            bytes.fill(0);
+            return;
+        }
+
+        bytes.copy_within(..len - byte_shift, byte_shift);
+        bytes[..byte_shift].fill(0);
+
+        if bit_shift == 0
+        {
+            return;
+        }
+
+        // working backwards, the carry byte has not been shifted yet
+        let mut i = len;
+        while i >= 8
+        {
+            let carry = if i > 8 { bytes[i - 9] } else { 0 };
+            let word = u64::from_le_bytes(bytes[i - 8..i].try_into().unwrap());
+            let word = (word << bit_shift) | (carry >> (8 - bit_shift)) as u64;
+
+            bytes[i - 8..i].copy_from_slice(&word.to_le_bytes());
+            i -= 8;
+        }
+
+        while i > 0
+        {
+            let carry = if i > 1 { bytes[i - 2] } else { 0 };
+
+            bytes[i - 1] = (bytes[i - 1] << bit_shift) | (carry >> (8 - bit_shift));
+            i -= 1;
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Shifts <b>bytes</b>, read as one little-endian number, <b>shift</b>
+    /// bits towards the first (least significant) byte, filling with zero.
+    fn shr_le(bytes: &mut [u8], shift: usize)
+    {
+        let len = bytes.len();
+        let byte_shift = shift / 8;
+        let bit_shift = (shift % 8) as u32;
+
+        if byte_shift >= len
+        {
+            bytes.fill(0);
+            return;
+        }
+
+        bytes.copy_within(byte_shift.., 0);
+        bytes[len - byte_shift..].fill(0);
+
+        if bit_shift == 0
+        {
+            return;
+        }
+
+        // working forwards, the carry byte has not been shifted yet
+        let mut i = 0;
+        while i + 8 <= len
+        {
+            let carry = if i + 8 < len { bytes[i + 8] } else { 0 };
+            let word = u64::from_le_bytes(bytes[i..i + 8].try_into().unwrap());
+            let word = (word >> bit_shift) | ((carry as u64) << (64 - bit_shift));
+
+            bytes[i..i + 8].copy_from_slice(&word.to_le_bytes());
+            i += 8;
+        }
+
+        while i < len
+        {
+            let carry = if i + 1 < len { bytes[i + 1] } else { 0 };
+
+            bytes[i] = (bytes[i] >> bit_shift) | (carry << (8 - bit_shift));
+            i += 1;
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Inverts every bit of the object in place.
+    pub fn invert(&mut self)
+    {
+        for b in self.bytes_mut().iter_mut()
+        {
+            *b = !*b;
+        }
+
+        self.clear_padding();
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Copies <b>count</b> bits of <b>src</b> starting at <b>src_start</b>
+    /// into the object starting at <b>dst_start</b>, up to 64 bits at a time.
+    fn copy_bits(&mut self,
+                 dst_start: usize,
+                 src: BitSlice,
+                 src_start: usize,
+                 count: usize) -> Result<(), BitFieldError>
+    {
+        let mut done = 0;
+
+        while done < count
+        {
+            let bits = cmp::min(count - done, u64::BITS as usize);
+            let value = src.retrieve_u64(src_start + done, src_start + done + bits - 1)?;
+
+            self.insert_u64(value, dst_start + done, dst_start + done + bits - 1)?;
+            done += bits;
+        }
+
+        Ok(())
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Shifts towards the least significant bit, which is the highest
+    /// numbered bit for <b>BitOrder::Msb0</b> and bit 0 otherwise.
+    ///
+    /// <p>In every bit order the bytes form one big or little-endian number
+    /// with the padding bits at its top (LSB0) or bottom (MSB0), so the shift
+    /// is a plain numeric shift of the bytes followed by clearing any bits
+    /// shifted into the padding.</p>
+    fn shift_right(&mut self, shift: usize)
+    {
+        match self.order
+        {
+            BitOrder::Lsb0Words(_) => Self::shr_le(self.bytes_mut(), shift),
+            _ => Self::shr_be(self.bytes_mut(), shift)
+        }
+
+        self.clear_padding();
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Shifts towards the most significant bit, which is bit 0 for
+    /// <b>BitOrder::Msb0</b> and the highest numbered bit otherwise.  See
+    /// <b>shift_right</b>.
+    fn shift_left(&mut self, shift: usize)
     {
-        if (stop_bit - start_bit + 1) > u8::BITS
+        match self.order
         {
-            return Err(BitFieldError::ExceededDataRange);
+            BitOrder::Lsb0Words(_) => Self::shl_le(self.bytes_mut(), shift),
+            _ => Self::shl_be(self.bytes_mut(), shift)
         }
 
-        self.insert_u64(value as u64, start_bit, stop_bit)
+        self.clear_padding();
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// The operations that need a Vec backed object to work in.
+#[cfg(feature = "alloc")]
+impl<S: BitStorage> BitField<S>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    /// Copies the object into one stored in a <b>Vec&lt;u8&gt;</b>.
+    pub fn to_bitfield(&self) -> BitField
+    {
+        BitField{storage: self.as_slice().to_vec(), bits: self.bits, order: self.order,
+                 overflow: self.overflow}
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Renders the object as a table of binary and hex values, as the
+    /// default <b>hex_dump</b> does.
+    pub fn debug_string(&self) -> String
+    {
+        self.hex_dump().to_string()
     }
 
     ///////////////////////////////////////////////////////////////////////////
-    /// Gets the value of the specified range of bits.
-    pub fn retrieve_u64(&self, start_bit: uint, stop_bit: uint) ->
-                                                     Result<u64, BitFieldError>
+    /// Combines <b>rhs</b> into the object using <b>op</b>, right aligned.
+    /// Only when the lengths or bit orders differ is a new buffer needed, and
+    /// the object then grows to the longer length.
+    fn combine_assign<T, F>(&mut self, rhs: &BitField<T>, op: F)
+        where T: BitStorage, F: Fn(u8, u8) -> u8
     {
-        // find out which byte range will be affected by the insert
-        let start_byte = start_bit / 8;
-        let stop_byte  = stop_bit / 8;
-
-        if start_bit > stop_bit
+        if self.bits == rhs.bits && self.order == rhs.order
         {
-            return Err(BitFieldError::NegativeRange);
+            for (r, b) in self.bytes_mut().iter_mut().zip(rhs.as_slice().iter())
+            {
+                *r = op(*r, *b);
+            }
         }
-        
-        // find the byte length for the data
-        let byte_length = (stop_byte - start_byte) + 1;
-        
-        if self.len() <= stop_byte
+        else
         {
-            return Err(BitFieldError::InvalidIndex)
-        }
+            let result = self.combine(rhs, Alignment::Right, op)
+                             .expect("Right alignment accepts any lengths");
 
-        if (stop_bit - start_bit + 1) > u64::BITS
-        {
-            return Err(BitFieldError::ExceededDataRange);
+            self.resize_bits(result.bits);
+            self.bytes_mut().copy_from_slice(result.as_slice());
         }
+    }
 
-        let mut value = 0u64;
-
-        // loop through the bytes to change the value of
-        for i in range(0, byte_length)
-        {
-            // find the start bit for the current byte
-            let mut current_start_bit = 8 * (start_byte + i);
-            if current_start_bit < start_bit
-            {
-                current_start_bit = start_bit;
-            }
-            // find the stop bit for the current byte
-            let end_of_byte_bit = 8 * (start_byte + i + 1) - 1;
-            let current_stop_bit =
-                if end_of_byte_bit > stop_bit
-                {
-                    stop_bit
-                }
-                else
-                {
-                    end_of_byte_bit
-                };
-            
-            // Find the current byte
-            let current_byte = start_byte + i;
-            
-            // Number bits in this byte that are going to be changed
-            let bits_in_byte = current_stop_bit - current_start_bit + 1;
-            
-            // Find mask and value for this byte
-            let mut byte_mask = BitField::get_mask(bits_in_byte);
-            
-            // If the current stop bit is not the end of the byte, then the
-            // value needs to be shifted by the difference
-            if i == (byte_length - 1)
-            {
-                let shift = end_of_byte_bit - current_stop_bit;
-
-                byte_mask = byte_mask << shift;
-                let tmp = (self.bytes[current_byte] & byte_mask) >> shift;
-                value = (value << bits_in_byte) | tmp as u64;
-            }
-            else
-            {
-                value = (value << bits_in_byte) |
-                               (self.bytes[current_byte] & byte_mask) as u64;
-            }
-        }
-        
-        Ok(value)
+    ///////////////////////////////////////////////////////////////////////////
+    /// Bitwise AND two BitField Objects lined up by <b>alignment</b>.  The
+    /// result is as long as the longer operand.
+    pub fn and_aligned<T: BitStorage>(&self, other: &BitField<T>, alignment: Alignment) ->
+                                                 Result<BitField, BitFieldError>
+    {
+        self.combine(other, alignment, |a, b| a & b)
     }
 
     ///////////////////////////////////////////////////////////////////////////
-    pub fn retrieve_uint(&self, start_bit: uint, stop_bit: uint) ->
-                                                     Result<uint, BitFieldError>
+    /// Bitwise OR two BitField Objects lined up by <b>alignment</b>.  The
+    /// result is as long as the longer operand.
+    pub fn or_aligned<T: BitStorage>(&self, other: &BitField<T>, alignment: Alignment) ->
+                                                 Result<BitField, BitFieldError>
     {
-        if (stop_bit - start_bit + 1) > uint::BITS
-        {
-            return Err(BitFieldError::ExceededDataRange);
-        }
-        
-        match self.retrieve_u64(start_bit, stop_bit)
-        {
-            Ok(v) => Ok(v as uint),
-            Err(e) => Err(e)
-        }
+        self.combine(other, alignment, |a, b| a | b)
+    }
 
+    ///////////////////////////////////////////////////////////////////////////
+    /// Bitwise XOR two BitField Objects lined up by <b>alignment</b>.  The
+    /// result is as long as the longer operand.
+    pub fn xor_aligned<T: BitStorage>(&self, other: &BitField<T>, alignment: Alignment) ->
+                                                 Result<BitField, BitFieldError>
+    {
+        self.combine(other, alignment, |a, b| a ^ b)
     }
 
     ///////////////////////////////////////////////////////////////////////////
-    pub fn retrieve_u32(&self, start_bit: uint, stop_bit: uint) ->
-                                                     Result<u32, BitFieldError>
+    /// Returns a copy of the object <b>bits</b> long in the given
+    /// <b>order</b>, with the two lined up by <b>alignment</b> and any extra
+    /// bits zero.
+    fn aligned_to(&self, bits: usize, order: BitOrder, alignment: Alignment) -> BitField
     {
-        if (stop_bit - start_bit + 1) > u32::BITS
-        {
-            return Err(BitFieldError::ExceededDataRange);
-        }
-        
-        match self.retrieve_u64(start_bit, stop_bit)
+        if self.bits == bits && self.order == order
         {
-            Ok(v) => Ok(v as u32),
-            Err(e) => Err(e)
+            return self.to_bitfield();
         }
 
+        let mut result = BitField::with_bit_capacity(bits).with_bit_order(order);
+        let least_significant_first = result.lsb_first() != (alignment == Alignment::Left);
+        let offset = if least_significant_first { 0 } else { bits - self.bits };
+
+        result.copy_bits(offset, self.as_bitslice(), 0, self.bits)
+              .expect("Aligned copy is within both objects");
+        result
     }
 
     ///////////////////////////////////////////////////////////////////////////
-    pub fn retrieve_u16(&self, start_bit: uint, stop_bit: uint) ->
-                                                     Result<u16, BitFieldError>
+    /// Combines two BitField Objects byte by byte using <b>op</b>, after
+    /// lining them up by <b>alignment</b> and zero extending the shorter.
+    /// The result uses the bit order of <b>self</b>.
+    fn combine<T, F>(&self, rhs: &BitField<T>, alignment: Alignment, op: F) ->
+                                                 Result<BitField, BitFieldError>
+        where T: BitStorage, F: Fn(u8, u8) -> u8
     {
-        if (stop_bit - start_bit + 1) > u16::BITS
+        if alignment == Alignment::Strict && self.bits != rhs.bits
         {
-            return Err(BitFieldError::ExceededDataRange);
+            return Err(BitFieldError::LengthMismatch{left_bits: self.bits,
+                                                     right_bits: rhs.bits});
         }
-        
-        match self.retrieve_u64(start_bit, stop_bit)
+
+        let bits = cmp::max(self.bits, rhs.bits);
+        let mut result = self.aligned_to(bits, self.order, alignment);
+        let rhs = rhs.aligned_to(bits, self.order, alignment);
+
+        for (r, b) in result.bytes_mut().iter_mut().zip(rhs.as_slice().iter())
         {
-            Ok(v) => Ok(v as u16),
-            Err(e) => Err(e)
+            *r = op(*r, *b);
         }
 
+        // the operators used all map zero padding to zero
+        Ok(result)
     }
 
     ///////////////////////////////////////////////////////////////////////////
-    pub fn retrieve_u8(&self, start_bit: uint, stop_bit: uint) ->
-                                                     Result<u8, BitFieldError>
+    /// Rotates every bit <b>rotate</b> places towards the most significant
+    /// bit, the same direction as <b>Shl</b>, with the bits shifted out
+    /// coming back in at the least significant end.
+    ///
+    /// <p>The rotation is over the bit length of the object, so padding bits
+    /// never take part.</p>
+    pub fn rotate_left(&mut self, rotate: usize)
     {
-        if (stop_bit - start_bit + 1) > u8::BITS
-        {
-            return Err(BitFieldError::ExceededDataRange);
-        }
-        
-        match self.retrieve_u64(start_bit, stop_bit)
+        if self.bits == 0 || rotate.is_multiple_of(self.bits)
         {
-            Ok(v) => Ok(v as u8),
-            Err(e) => Err(e)
+            return;
         }
 
+        let rotate = rotate % self.bits;
+        let mut wrapped = self.to_bitfield();
+
+        wrapped.shift_right(self.bits - rotate);
+        self.shift_left(rotate);
+        self.combine_assign(&wrapped, |a, b| a | b);
     }
 
     ///////////////////////////////////////////////////////////////////////////
-    pub fn debug_string(&self) -> String
+    /// Rotates every bit <b>rotate</b> places towards the least significant
+    /// bit, the same direction as <b>Shr</b>.  See <b>rotate_left</b>.
+    pub fn rotate_right(&mut self, rotate: usize)
     {
-        let mut s = String::new();
-
-        s.push_str("Offset  Binary                                Hex      \n");
-        s.push_str("=======================================================\n");
-
-        let mut i = 0;
-
-        while i < (self.len() / 4)
+        if self.bits != 0
         {
-            s.push_str(format!("{:06}: ", i * 4).as_slice());
-            
-            s.push_str(
-                format!("{:08b} {:08b} {:08b} {:08b}   ",
-                        self.bytes[i * 4],
-                        self.bytes[i * 4 + 1],
-                        self.bytes[i * 4 + 2],
-                        self.bytes[i * 4 + 3]).as_slice());
-
-            s.push_str(
-                format!("{:02x}{:02x} {:02x}{:02x}\n",
-                        self.bytes[i * 4],
-                        self.bytes[i * 4 + 1],
-                        self.bytes[i * 4 + 2],
-                        self.bytes[i * 4 + 3]).as_slice());
-         
-            i = i + 1;
+            self.rotate_left(self.bits - rotate % self.bits);
         }
+    }
 
-        if i * 4 != self.len()
-        {
-            let mut second_bits = "".to_string();
-            let mut second_byte = "".to_string();
-            let mut third_bits = "".to_string();
-            let mut third_byte = "".to_string();
-
-            if (self.len() % 4) > 1
-            {
-                second_bits = format!("{:08b}", self.bytes[i * 4 + 1]);
-                second_byte = format!("{:02x}", self.bytes[i * 4 + 1]);
-            }
-            
-            if (self.len() % 4) > 2
-            {
-                third_bits = format!("{:08b}", self.bytes[i * 4 + 2]);
-                third_byte = format!("{:02x}", self.bytes[i * 4 + 2]);
-            }
-        
-            s.push_str(format!("{:06}: ", i * 4).as_slice());
-            
-            s.push_str(format!("{:08b} {:08} {:08} {:08}   ",
-                               self.bytes[i * 4],
-                               second_bits,
-                               third_bits,
-                               "").as_slice());
-
-            s.push_str(format!("{:02x}{:02} {:02}{:02}\n",
-                               self.bytes[i * 4],
-                               second_byte,
-                               third_byte,
-                               "").as_slice());
-        }
+    ///////////////////////////////////////////////////////////////////////////
+    /// Rotates the bits in <b>range</b> <b>rotate</b> places towards the most
+    /// significant bit, leaving the rest of the object untouched.
+    pub fn rotate_range_left<R: RangeBounds<usize>>(&mut self, range: R, rotate: usize) ->
+                                                       Result<(), BitFieldError>
+    {
+        let (start_bit, stop_bit) = self.bit_range(range)?;
+        let count = stop_bit - start_bit + 1;
+        let mut window = BitField::with_bit_capacity(count).with_bit_order(self.order);
 
-        s
+        window.copy_bits(0, self.as_bitslice(), start_bit, count)?;
+        window.rotate_left(rotate);
+        self.copy_bits(start_bit, window.as_bitslice(), 0, count)
     }
-    
-    ///////////////////////////////////////////////////////////////////////////
+
     ///////////////////////////////////////////////////////////////////////////
+    /// Rotates the bits in <b>range</b> <b>rotate</b> places towards the
+    /// least significant bit.  See <b>rotate_range_left</b>.
+    pub fn rotate_range_right<R: RangeBounds<usize>>(&mut self, range: R, rotate: usize) ->
+                                                        Result<(), BitFieldError>
+    {
+        let (start_bit, stop_bit) = self.bit_range(range)?;
+        let count = stop_bit - start_bit + 1;
 
+        self.rotate_range_left(start_bit..=stop_bit, count - rotate % count)
+    }
 }
-   
+
 ///////////////////////////////////////////////////////////////////////////////
-impl BitAnd<BitField, BitField> for BitField
+#[cfg(feature = "alloc")]
+impl<S: BitStorage, T: BitStorage> BitAnd<&BitField<T>> for &BitField<S>
 {
+    type Output = BitField;
+
     ///////////////////////////////////////////////////////////////////////////
     /// Bitwise AND two BitField Objects together.
     ///
-    /// LIMITATION:
-    /// If the two BitField Objects differ in size the bitwise AND will start
-    /// with the last byte and continue until one of the objects first byte is
-    /// reached.
+    /// If the two BitField Objects differ in size they are right aligned, as
+    /// by <b>and_aligned</b> with <b>Alignment::Right</b>, and the result is
+    /// as long as the longer.  Use <b>and_aligned</b> for other alignments.
     /// (e.g)
     /// BitField1 containing 2 bytes set to FF and FF (0xFFFF) bitwise AND with
     /// BitField2 containting 1 byte set to 00 (0x55) will result in
@@ -482,256 +1278,442 @@ impl BitAnd<BitField, BitField> for BitField
     /// BitField2 & BitField1 returns
     ///       BitField containg 2 bytes set to 00 and 55 (0x0055)
     ///
-    fn bitand(&self, _rhs: &BitField) -> BitField
+    fn bitand(self, rhs: &BitField<T>) -> BitField
     {
-        let len = cmp::max(self.len(), _rhs.len());
-        let mut result = BitField::with_capacity(len);
-        let (mut i, mut j, mut k) = (self.len() - 1,
-                                     _rhs.len() - 1,
-                                     len - 1);
-        loop
-        {
-            if i == -1 || j == -1
-            {
-                break;
-            }
+        self.and_aligned(rhs, Alignment::Right)
+            .expect("Right alignment accepts any lengths")
+    }
+}
 
-            match result.bytes.get_mut(k)
-            {
-                Some(b) => *b = self.bytes[i] & _rhs.bytes[j],
-                None => panic!("I don't know we got here...")
-            };
+///////////////////////////////////////////////////////////////////////////////
+#[cfg(feature = "alloc")]
+impl<S: BitStorage, T: BitStorage> BitOr<&BitField<T>> for &BitField<S>
+{
+    type Output = BitField;
 
-            i = i - 1;
-            j = j - 1;
-            k = k - 1;
-        }
-        
-        result
+    ///////////////////////////////////////////////////////////////////////////
+    /// Bitwise OR two BitField Objects together, right aligned.  Bits of the
+    /// longer object with no counterpart are copied.
+    fn bitor(self, rhs: &BitField<T>) -> BitField
+    {
+        self.or_aligned(rhs, Alignment::Right)
+            .expect("Right alignment accepts any lengths")
     }
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-impl BitOr<BitField, BitField> for BitField
+#[cfg(feature = "alloc")]
+impl<S: BitStorage, T: BitStorage> BitXor<&BitField<T>> for &BitField<S>
 {
+    type Output = BitField;
+
     ///////////////////////////////////////////////////////////////////////////
-    fn bitor(&self, _rhs: &BitField) -> BitField
+    /// Bitwise XOR two BitField Objects together, right aligned.  Bits of the
+    /// longer object with no counterpart are copied.
+    fn bitxor(self, rhs: &BitField<T>) -> BitField
+    {
+        self.xor_aligned(rhs, Alignment::Right)
+            .expect("Right alignment accepts any lengths")
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// By-value and mixed forms of the binary operators forward to the
+// by-reference implementations above.
+macro_rules! forward_binop
+{
+    ($imp:ident, $method:ident) =>
     {
-        let mut result =
-            if self.len() < _rhs.len()
+        #[cfg(feature = "alloc")]
+        impl<S: BitStorage, T: BitStorage> $imp<BitField<T>> for BitField<S>
+        {
+            type Output = BitField;
+
+            fn $method(self, rhs: BitField<T>) -> BitField
             {
-                _rhs.clone()
+                $imp::$method(&self, &rhs)
             }
-            else
-            {
-                self.clone()
-            };
+        }
 
-        let (mut i, mut j, mut k) = (self.len() - 1,
-                                     _rhs.len() - 1,
-                                     result.len() - 1);
-        loop
+        #[cfg(feature = "alloc")]
+        impl<S: BitStorage, T: BitStorage> $imp<&BitField<T>> for BitField<S>
         {
-            if i == -1 || j == -1
+            type Output = BitField;
+
+            fn $method(self, rhs: &BitField<T>) -> BitField
             {
-                break;
+                $imp::$method(&self, rhs)
             }
+        }
 
-            match result.bytes.get_mut(k)
-            {
-                Some(b) => *b = self.bytes[i] | _rhs.bytes[j],
-                None => panic!("I don't know we got here...")
-            };
+        #[cfg(feature = "alloc")]
+        impl<S: BitStorage, T: BitStorage> $imp<BitField<T>> for &BitField<S>
+        {
+            type Output = BitField;
 
-            i = i - 1;
-            j = j - 1;
-            k = k - 1;
+            fn $method(self, rhs: BitField<T>) -> BitField
+            {
+                $imp::$method(self, &rhs)
+            }
         }
-        
-        result
     }
 }
 
+forward_binop!(BitAnd, bitand);
+forward_binop!(BitOr, bitor);
+forward_binop!(BitXor, bitxor);
+
 ///////////////////////////////////////////////////////////////////////////////
-impl BitXor<BitField, BitField> for BitField
+// The compound assignment forms update the left operand's bytes in place
+// when both operands have the same length and bit order.  Otherwise it grows
+// to the longer length, which panics if its storage can not hold it.
+macro_rules! assign_binop
 {
-    ///////////////////////////////////////////////////////////////////////////
-    fn bitxor(&self, _rhs: &BitField) -> BitField
+    ($imp:ident, $method:ident, $op:tt) =>
     {
-        let mut result =
-            if self.len() < _rhs.len()
+        #[cfg(feature = "alloc")]
+        impl<S: BitStorage, T: BitStorage> $imp<&BitField<T>> for BitField<S>
+        {
+            fn $method(&mut self, rhs: &BitField<T>)
             {
-                _rhs.clone()
+                self.combine_assign(rhs, |a, b| a $op b);
             }
-            else
-            {
-                self.clone()
-            };
+        }
 
-        let (mut i, mut j, mut k) = (self.len() - 1,
-                                     _rhs.len() - 1,
-                                     result.len() - 1);
-        loop
+        #[cfg(feature = "alloc")]
+        impl<S: BitStorage, T: BitStorage> $imp<BitField<T>> for BitField<S>
         {
-            if i == -1 || j == -1
+            fn $method(&mut self, rhs: BitField<T>)
             {
-                break;
+                self.combine_assign(&rhs, |a, b| a $op b);
             }
+        }
+    }
+}
 
-            match result.bytes.get_mut(k)
-            {
-                Some(b) => *b = self.bytes[i] ^ _rhs.bytes[j],
-                None => panic!("I don't know we got here...")
-            };
+assign_binop!(BitAndAssign, bitand_assign, &);
+assign_binop!(BitOrAssign, bitor_assign, |);
+assign_binop!(BitXorAssign, bitxor_assign, ^);
 
-            i = i - 1;
-            j = j - 1;
-            k = k - 1;
-        }
-        
-        result
+///////////////////////////////////////////////////////////////////////////////
+impl<S: BitStorage + Default> Default for BitField<S>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn default() -> BitField<S>
+    {
+        BitField{storage: S::default(), bits: 0, order: BitOrder::Msb0, overflow: None}
     }
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-impl Index<uint, u8> for BitField
+/// Objects are equal when their bits, bit orders and bytes in use are, so
+/// any storage beyond <b>len()</b> is ignored.
+impl<S: BitStorage, T: BitStorage> PartialEq<BitField<T>> for BitField<S>
 {
     ///////////////////////////////////////////////////////////////////////////
-    fn index<'a>(&'a self, index: &uint) -> &'a u8
+    fn eq(&self, other: &BitField<T>) -> bool
     {
-        &self.bytes[*index]
+        self.bits == other.bits && self.order == other.order &&
+            self.as_slice() == other.as_slice()
     }
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-impl IndexMut<uint, u8> for BitField
+impl<S: BitStorage> Eq for BitField<S> {}
+
+///////////////////////////////////////////////////////////////////////////////
+impl<S: BitStorage> Hash for BitField<S>
 {
     ///////////////////////////////////////////////////////////////////////////
-    fn index_mut<'a>(&'a mut self, index: &uint) -> &'a mut u8
+    fn hash<H: Hasher>(&self, state: &mut H)
     {
-        &mut self.bytes.as_mut_slice()[*index]
+        self.as_slice().hash(state);
+        self.bits.hash(state);
+        self.order.hash(state);
     }
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-impl Not<BitField> for BitField
+impl<S: BitStorage> Index<usize> for BitField<S>
 {
+    type Output = u8;
+
     ///////////////////////////////////////////////////////////////////////////
-    fn not(&self) -> BitField
+    fn index(&self, index: usize) -> &u8
     {
-        
-        let mut result = BitField::with_capacity(self.len());
+        &self.as_slice()[index]
+    }
+}
 
-        for i in range(0, self.len())
-        {
-            match result.bytes.get_mut(i)
-            {
-                Some(b) => *b = !self.bytes[i],
-                None => panic!("I don't know we got here...")
-            };
-        }
+///////////////////////////////////////////////////////////////////////////////
+impl<S: BitStorage> IndexMut<usize> for BitField<S>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn index_mut(&mut self, index: usize) -> &mut u8
+    {
+        &mut self.bytes_mut()[index]
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl<S: BitStorage + Clone> Not for &BitField<S>
+{
+    type Output = BitField<S>;
 
+    ///////////////////////////////////////////////////////////////////////////
+    fn not(self) -> BitField<S>
+    {
+        let mut result = self.clone();
+        result.invert();
         result
     }
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-impl Shr<uint, BitField> for BitField
+impl<S: BitStorage> Not for BitField<S>
 {
+    type Output = BitField<S>;
+
     ///////////////////////////////////////////////////////////////////////////
-    fn shr(&self, _rhs: &uint) -> BitField
+    fn not(mut self) -> BitField<S>
     {
-        let mut result = BitField::with_capacity(self.len());
+        self.invert();
+        self
+    }
+}
 
-        let (mut i, mut j) = (0, *_rhs);
+///////////////////////////////////////////////////////////////////////////////
+impl<S: BitStorage + Clone> Shr<usize> for &BitField<S>
+{
+    type Output = BitField<S>;
 
-        while j < (self.len() * 8)
-        {
-            if (j + 7) > (self.len() * 8 - 1)
-            {
-                let partial = (self.len() * 8 -1) -j;
-                
-                result.insert_u64(self.retrieve_u64(i, i + partial).unwrap(),
-                              j, j + partial).unwrap();
-            }
-            else
-            {
-                result.insert_u64(self.retrieve_u64(i, i + 7).unwrap(),
-                              j, j + 7).unwrap();
-            }
+    ///////////////////////////////////////////////////////////////////////////
+    fn shr(self, rhs: usize) -> BitField<S>
+    {
+        let mut result = self.clone();
+        result.shift_right(rhs);
+        result
+    }
+}
 
-            i = i + 8;
-            j = j + 8;
-        }
+///////////////////////////////////////////////////////////////////////////////
+impl<S: BitStorage> Shr<usize> for BitField<S>
+{
+    type Output = BitField<S>;
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn shr(mut self, rhs: usize) -> BitField<S>
+    {
+        self.shift_right(rhs);
+        self
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl<S: BitStorage> ShrAssign<usize> for BitField<S>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn shr_assign(&mut self, rhs: usize)
+    {
+        self.shift_right(rhs);
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl<S: BitStorage + Clone> Shl<usize> for &BitField<S>
+{
+    type Output = BitField<S>;
 
+    ///////////////////////////////////////////////////////////////////////////
+    fn shl(self, rhs: usize) -> BitField<S>
+    {
+        let mut result = self.clone();
+        result.shift_left(rhs);
         result
     }
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-impl Shl<uint, BitField> for BitField
+impl<S: BitStorage> Shl<usize> for BitField<S>
 {
+    type Output = BitField<S>;
+
     ///////////////////////////////////////////////////////////////////////////
-    fn shl(&self, _rhs: &uint) -> BitField
+    fn shl(mut self, rhs: usize) -> BitField<S>
     {
-        let mut result = BitField::with_capacity(self.len());
+        self.shift_left(rhs);
+        self
+    }
+}
 
-        let (mut i, mut j) = (0, *_rhs);
+///////////////////////////////////////////////////////////////////////////////
+impl<S: BitStorage> ShlAssign<usize> for BitField<S>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn shl_assign(&mut self, rhs: usize)
+    {
+        self.shift_left(rhs);
+    }
+}
 
-        while j < (self.len() * 8)
-        {
-            if (j + 7) > (self.len() * 8 - 1)
-            {
-                let partial = (self.len() * 8 -1) -j;
-                
-                result.insert_u64(self.retrieve_u64(j, j + partial).unwrap(),
-                              i, i + partial).unwrap();
-            }
-            else
-            {
-                result.insert_u64(self.retrieve_u64(j, j + 7).unwrap(),
-                              i, i + 7).unwrap();
-            }
+///////////////////////////////////////////////////////////////////////////////
+impl<S: BitStorage> AsRef<[u8]> for BitField<S>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn as_ref(&self) -> &[u8]
+    {
+        self.as_slice()
+    }
+}
 
-            i = i + 8;
-            j = j + 8;
-        }
+///////////////////////////////////////////////////////////////////////////////
+/// Writes the object as a hexadecimal number, see <b>LowerHex</b>.
+impl<S: BitStorage> fmt::Display for BitField<S>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result
+    {
+        fmt::LowerHex::fmt(self, fmt)
+    }
+}
 
-        result
+///////////////////////////////////////////////////////////////////////////////
+impl<S: BitStorage> fmt::Debug for BitField<S>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result
+    {
+        fmt.debug_struct("BitField")
+           .field("bits", &self.bits)
+           .field("order", &self.order)
+           .field("value", &format_args!("{:#x}", self))
+           .finish()
     }
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-impl AsSlice<u8> for BitField
+/// Writes the object as a binary number of exactly <b>bit_len()</b> digits.
+///
+/// <p>Bit 0 is the most significant digit for MSB0 objects, otherwise the
+/// highest numbered bit is, as when reading the whole object with
+/// <b>retrieve_u64</b>.  The <b>#</b> flag adds a <b>0b</b> prefix, width,
+/// fill, alignment and <b>0</b> pad work as for integers, and <b>+</b>
+/// separates the digits into bytes from the least significant end with
+/// <b>_</b>.</p>
+impl<S: BitStorage> fmt::Binary for BitField<S>
 {
     ///////////////////////////////////////////////////////////////////////////
-    fn as_slice<'a>(&'a self) -> &'a [u8]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result
     {
-        self.bytes.as_slice()
+        fmt::Binary::fmt(&self.as_bitslice(), fmt)
     }
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-impl fmt::Show for BitField
+/// Writes the object as an octal number, see <b>Binary</b>.  The first
+/// digit covers the bits left over when the length is not a multiple of 3.
+impl<S: BitStorage> fmt::Octal for BitField<S>
 {
     ///////////////////////////////////////////////////////////////////////////
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result
     {
-        let mut i = 0;
-        while i < self.len() - 1
+        fmt::Octal::fmt(&self.as_bitslice(), fmt)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// Writes the object as a hexadecimal number, see <b>Binary</b>.  The first
+/// digit covers the bits left over when the length is not a multiple of 4,
+/// and <b>+</b> separates groups of 4 digits.
+impl<S: BitStorage> fmt::LowerHex for BitField<S>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result
+    {
+        fmt::LowerHex::fmt(&self.as_bitslice(), fmt)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// See <b>LowerHex</b>.
+impl<S: BitStorage> fmt::UpperHex for BitField<S>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result
+    {
+        fmt::UpperHex::fmt(&self.as_bitslice(), fmt)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[cfg(feature = "alloc")]
+impl FromIterator<bool> for BitField
+{
+    ///////////////////////////////////////////////////////////////////////////
+    /// Builds an MSB0 object with one bit per item, from bit 0.
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> BitField
+    {
+        let mut result = BitField::new();
+        result.extend(iter);
+        result
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl<S: BitStorage> Extend<bool> for BitField<S>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    /// Appends one bit per item after the highest numbered bit.
+    fn extend<I: IntoIterator<Item = bool>>(&mut self, iter: I)
+    {
+        for value in iter
         {
-            if i != 0 { try!(write!(fmt, " ")); }
-            try!(write!(fmt, "{:02x}", self.bytes[i]));
-            try!(write!(fmt, "{:02x}", self.bytes[i + 1]));
-            i = i + 2;
+            self.push_bit(value);
         }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl<'a, S: BitStorage> IntoIterator for &'a BitField<S>
+{
+    type Item = bool;
+    type IntoIter = Iter<'a>;
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn into_iter(self) -> Iter<'a>
+    {
+        self.iter()
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// Appends each byte written as by <b>push_bits</b> with a width of 8, so
+/// an MSB0 object receives the bytes unchanged.  Fixed size storage accepts
+/// bytes until it is full.
+#[cfg(feature = "std")]
+impl<S: BitStorage> io::Write for BitField<S>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize>
+    {
+        let mut written = 0;
 
-        if i == self.len() - 1
+        for &byte in buf
         {
-            if i != 0 { try!(write!(fmt, " ")); }
-            try!(write!(fmt, "{:02x}", self.bytes[i]));
+            if self.push_bits(byte as u64, 8).is_err()
+            {
+                break;
+            }
+
+            written += 1;
         }
 
+        Ok(written)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn flush(&mut self) -> io::Result<()>
+    {
         Ok(())
     }
 }
@@ -0,0 +1,2305 @@
+use core::cmp;
+use core::fmt::{self, Write};
+use core::iter::FusedIterator;
+use core::ops::{Bound, RangeBounds};
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+use crate::bitfield::{BitField, BitFieldError, BitOrder, BitStore, ByteOrder, FixedPoint,
+                      OverflowPolicy, Rounding, SignedEncoding};
+use crate::storage::BitStorage;
+
+///////////////////////////////////////////////////////////////////////////////
+// Generates methods that forward to the view returned by <b>as_bitslice</b>.
+macro_rules! forward_reads
+{
+    ($($(#[$attr:meta])* fn $name:ident $([$($g:tt)*])? ($($arg:ident: $t:ty),*) -> $ret:ty;)*) =>
+    {
+        $(
+            #[doc = concat!("See <b>BitSlice::", stringify!($name), "</b>.")]
+            $(#[$attr])*
+            pub fn $name $(<$($g)*>)? (&self $(, $arg: $t)*) -> $ret
+            {
+                self.as_bitslice().$name($($arg),*)
+            }
+        )*
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Generates methods that forward to the view returned by
+// <b>as_mut_bitslice</b>.
+macro_rules! forward_writes
+{
+    ($(fn $name:ident $([$($g:tt)*])? ($($arg:ident: $t:ty),*) -> $ret:ty;)*) =>
+    {
+        $(
+            #[doc = concat!("See <b>BitSliceMut::", stringify!($name), "</b>.")]
+            pub fn $name $(<$($g)*>)? (&mut self $(, $arg: $t)*) -> $ret
+            {
+                self.as_mut_bitslice().$name($($arg),*)
+            }
+        )*
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// The reading methods of BitSlice, for the types that can lend one.
+macro_rules! bit_slice_reads
+{
+    () =>
+    {
+        $crate::bitslice::forward_reads!
+        {
+            fn slice[R: RangeBounds<usize>](range: R) -> Result<BitSlice<'_>, BitFieldError>;
+            fn get_bit(bit: usize) -> Result<bool, BitFieldError>;
+            fn bit_range[R: RangeBounds<usize>](range: R) -> Result<(usize, usize), BitFieldError>;
+            fn get[T: BitStore, R: RangeBounds<usize>](range: R) -> Result<T, BitFieldError>;
+            #[cfg(feature = "alloc")]
+            fn retrieve_bytes(start_bit: usize, stop_bit: usize) -> Result<Vec<u8>, BitFieldError>;
+            fn retrieve_u64(start_bit: usize, stop_bit: usize) -> Result<u64, BitFieldError>;
+            fn retrieve_usize(start_bit: usize, stop_bit: usize) -> Result<usize, BitFieldError>;
+            fn retrieve_u32(start_bit: usize, stop_bit: usize) -> Result<u32, BitFieldError>;
+            fn retrieve_u16(start_bit: usize, stop_bit: usize) -> Result<u16, BitFieldError>;
+            fn retrieve_u8(start_bit: usize, stop_bit: usize) -> Result<u8, BitFieldError>;
+            fn retrieve_signed(start_bit: usize, stop_bit: usize, encoding: SignedEncoding) ->
+                                                               Result<i64, BitFieldError>;
+            fn retrieve_i64(start_bit: usize, stop_bit: usize) -> Result<i64, BitFieldError>;
+            fn retrieve_i32(start_bit: usize, stop_bit: usize) -> Result<i32, BitFieldError>;
+            fn retrieve_i16(start_bit: usize, stop_bit: usize) -> Result<i16, BitFieldError>;
+            fn retrieve_i8(start_bit: usize, stop_bit: usize) -> Result<i8, BitFieldError>;
+            fn retrieve_endian(start_bit: usize, stop_bit: usize, byte_order: ByteOrder) ->
+                                                               Result<u64, BitFieldError>;
+            fn retrieve_f32(start_bit: usize) -> Result<f32, BitFieldError>;
+            fn retrieve_f64(start_bit: usize) -> Result<f64, BitFieldError>;
+            fn retrieve_fixed(start_bit: usize, stop_bit: usize, format: &FixedPoint) ->
+                                                               Result<f64, BitFieldError>;
+            fn lsb_first() -> bool;
+            fn count_ones() -> usize;
+            fn count_zeros() -> usize;
+            fn leading_zeros() -> usize;
+            fn trailing_zeros() -> usize;
+            fn first_set() -> Option<usize>;
+            fn last_set() -> Option<usize>;
+            fn parity() -> bool;
+            fn count_ones_range[R: RangeBounds<usize>](range: R) -> Result<usize, BitFieldError>;
+            fn count_zeros_range[R: RangeBounds<usize>](range: R) -> Result<usize, BitFieldError>;
+            fn leading_zeros_range[R: RangeBounds<usize>](range: R) -> Result<usize, BitFieldError>;
+            fn trailing_zeros_range[R: RangeBounds<usize>](range: R) -> Result<usize, BitFieldError>;
+            fn first_set_range[R: RangeBounds<usize>](range: R) ->
+                                                     Result<Option<usize>, BitFieldError>;
+            fn last_set_range[R: RangeBounds<usize>](range: R) ->
+                                                     Result<Option<usize>, BitFieldError>;
+            fn parity_range[R: RangeBounds<usize>](range: R) -> Result<bool, BitFieldError>;
+            fn iter() -> Iter<'_>;
+            fn iter_ones() -> BitIndices<'_>;
+            fn iter_zeros() -> BitIndices<'_>;
+            fn chunks(width: usize) -> Chunks<'_>;
+            fn windows(width: usize) -> Windows<'_>;
+            fn find_pattern['p](pattern: impl Into<BitSlice<'p>>) -> Option<usize>;
+            fn rfind_pattern['p](pattern: impl Into<BitSlice<'p>>) -> Option<usize>;
+            #[cfg(feature = "alloc")]
+            fn find_all_patterns['p](pattern: impl Into<BitSlice<'p>>) -> Vec<usize>;
+            fn find_pattern_within['p](pattern: impl Into<BitSlice<'p>>, max_errors: usize) ->
+                                                                          Option<usize>;
+            fn rfind_pattern_within['p](pattern: impl Into<BitSlice<'p>>, max_errors: usize) ->
+                                                                          Option<usize>;
+            #[cfg(feature = "alloc")]
+            fn find_all_patterns_within['p](pattern: impl Into<BitSlice<'p>>, max_errors: usize) ->
+                                                                          Vec<usize>;
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// The writing methods of BitSliceMut, for the types that can lend one.
+macro_rules! bit_slice_writes
+{
+    () =>
+    {
+        $crate::bitslice::forward_writes!
+        {
+            fn set_bit(bit: usize) -> Result<(), BitFieldError>;
+            fn clear_bit(bit: usize) -> Result<(), BitFieldError>;
+            fn toggle_bit(bit: usize) -> Result<(), BitFieldError>;
+            fn assign_bit(bit: usize, value: bool) -> Result<(), BitFieldError>;
+            fn set_range(start_bit: usize, stop_bit: usize) -> Result<(), BitFieldError>;
+            fn clear_range(start_bit: usize, stop_bit: usize) -> Result<(), BitFieldError>;
+            fn toggle_range(start_bit: usize, stop_bit: usize) -> Result<(), BitFieldError>;
+            fn set[T: BitStore, R: RangeBounds<usize>](range: R, value: T) -> Result<(), BitFieldError>;
+            fn set_with[T: BitStore, R: RangeBounds<usize>](range: R, value: T,
+                                                            overflow: OverflowPolicy) ->
+                                                                Result<(), BitFieldError>;
+            fn insert_bytes(value: &[u8], start_bit: usize, stop_bit: usize) ->
+                                                                Result<(), BitFieldError>;
+            fn insert_u64(value: u64, start_bit: usize, stop_bit: usize) -> Result<(), BitFieldError>;
+            fn insert_u64_with(value: u64, start_bit: usize, stop_bit: usize,
+                               overflow: OverflowPolicy) -> Result<(), BitFieldError>;
+            fn insert_usize(value: usize, start_bit: usize, stop_bit: usize) ->
+                                                                Result<(), BitFieldError>;
+            fn insert_u32(value: u32, start_bit: usize, stop_bit: usize) -> Result<(), BitFieldError>;
+            fn insert_u16(value: u16, start_bit: usize, stop_bit: usize) -> Result<(), BitFieldError>;
+            fn insert_u8(value: u8, start_bit: usize, stop_bit: usize) -> Result<(), BitFieldError>;
+            fn insert_signed(value: i64, start_bit: usize, stop_bit: usize, encoding: SignedEncoding) ->
+                                                                Result<(), BitFieldError>;
+            fn insert_signed_with(value: i64, start_bit: usize, stop_bit: usize,
+                                  encoding: SignedEncoding, overflow: OverflowPolicy) ->
+                                                                Result<(), BitFieldError>;
+            fn insert_i64(value: i64, start_bit: usize, stop_bit: usize) -> Result<(), BitFieldError>;
+            fn insert_i32(value: i32, start_bit: usize, stop_bit: usize) -> Result<(), BitFieldError>;
+            fn insert_i16(value: i16, start_bit: usize, stop_bit: usize) -> Result<(), BitFieldError>;
+            fn insert_i8(value: i8, start_bit: usize, stop_bit: usize) -> Result<(), BitFieldError>;
+            fn insert_endian(value: u64, start_bit: usize, stop_bit: usize, byte_order: ByteOrder) ->
+                                                                Result<(), BitFieldError>;
+            fn insert_f32(value: f32, start_bit: usize) -> Result<(), BitFieldError>;
+            fn insert_f64(value: f64, start_bit: usize) -> Result<(), BitFieldError>;
+            fn insert_fixed(value: f64, start_bit: usize, stop_bit: usize, format: &FixedPoint) ->
+                                                                Result<(), BitFieldError>;
+            fn insert_fixed_with(value: f64, start_bit: usize, stop_bit: usize,
+                                 format: &FixedPoint, overflow: OverflowPolicy) ->
+                                                                Result<(), BitFieldError>;
+        }
+    }
+}
+
+pub(crate) use {bit_slice_reads, bit_slice_writes, forward_reads, forward_writes};
+
+///////////////////////////////////////////////////////////////////////////////
+/// A borrowed, read only view of a run of bits.
+///
+/// <p>The view numbers its bits from 0, starting <b>offset</b> bits into
+/// the bytes it borrows, which are numbered following <b>order</b> exactly
+/// as those of a BitField.  Sub-slicing never copies.</p>
+#[derive(Clone, Copy, Debug)]
+pub struct BitSlice<'a>
+{
+    bytes: &'a [u8],
+    order: BitOrder,
+    offset: usize,
+    bits: usize
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// A borrowed view of a run of bits that can be written.  See
+/// <b>BitSlice</b>.
+#[derive(Debug)]
+pub struct BitSliceMut<'a>
+{
+    bytes: &'a mut [u8],
+    order: BitOrder,
+    offset: usize,
+    bits: usize,
+    overflow: Option<OverflowPolicy>
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl<'a> BitSlice<'a>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    /// Creates an MSB0 view of every bit of <b>bytes</b>.
+    pub fn new(bytes: &'a [u8]) -> BitSlice<'a>
+    {
+        BitSlice::from_parts(bytes, BitOrder::Msb0, 0, bytes.len() * 8)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub(crate) fn from_parts(bytes: &'a [u8], order: BitOrder, offset: usize, bits: usize) ->
+                                                                      BitSlice<'a>
+    {
+        BitSlice{bytes, order, offset, bits}
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the view using the given bit numbering <b>order</b> for the
+    /// bytes it borrows.
+    pub fn with_bit_order(mut self, order: BitOrder) -> BitSlice<'a>
+    {
+        if let BitOrder::Lsb0Words(0) = order
+        {
+            panic!("Word width must be at least one byte");
+        }
+
+        self.order = order;
+        self
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn bit_order(&self) -> BitOrder
+    {
+        self.order
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn bit_len(&self) -> usize
+    {
+        self.bits
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn is_empty(&self) -> bool
+    {
+        self.bits == 0
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns a view of the bits in <b>range</b>, numbered from 0, without
+    /// copying them.
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> Result<BitSlice<'a>, BitFieldError>
+    {
+        let (start_bit, stop_bit) = self.bit_range(range)?;
+
+        Ok(BitSlice::from_parts(self.bytes, self.order, self.offset + start_bit,
+                                stop_bit - start_bit + 1))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Copies the bits of the view into a new BitField of the same bit
+    /// order.
+    #[cfg(feature = "alloc")]
+    pub fn to_bitfield(&self) -> BitField
+    {
+        let mut result = BitField::new().with_bit_order(self.order);
+
+        result.extend_from_bitslice(*self);
+        result
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub(crate) fn get_mask(bits: usize) -> u8
+    {
+        ((1u32 << bits) - 1u32) as u8
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the index into the bytes holding bits 8 * <b>byte</b> through
+    /// 8 * <b>byte</b> + 7.
+    pub(crate) fn physical_byte(&self, byte: usize) -> usize
+    {
+        match self.order
+        {
+            // little-endian words of any width keep their bytes in storage
+            // order, only the display of the words differs
+            BitOrder::Msb0 | BitOrder::Lsb0Words(_) => byte,
+            BitOrder::Lsb0 => self.bytes.len() - 1 - byte,
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// True when bit 8n is the least significant bit of its byte and ranges
+    /// hold their least significant bit at the start.
+    pub fn lsb_first(&self) -> bool
+    {
+        self.order != BitOrder::Msb0
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// For the range <b>start_bit</b> to <b>stop_bit</b>, returns the bit
+    /// range within the part of a value held by the given byte: the amount
+    /// the part is shifted within the stored byte, and the amount it is
+    /// shifted within the value.
+    pub(crate) fn byte_shifts(&self, byte: usize, start_bit: usize, stop_bit: usize) ->
+                                                          (usize, usize, usize)
+    {
+        let lo = cmp::max(start_bit, 8 * byte);
+        let hi = cmp::min(stop_bit, 8 * byte + 7);
+
+        if self.lsb_first()
+        {
+            (hi - lo + 1, lo % 8, lo - start_bit)
+        }
+        else
+        {
+            (hi - lo + 1, 7 - hi % 8, stop_bit - hi)
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the <b>n</b>th group of up to 8 bits of the range
+    /// <b>start_bit</b> to <b>stop_bit</b>, counting from its least
+    /// significant end, or None if the range is exhausted.
+    fn significant_chunk(&self, n: usize, start_bit: usize, stop_bit: usize) ->
+                                                       Option<(usize, usize)>
+    {
+        let offset = n.checked_mul(8)?;
+
+        if offset > stop_bit - start_bit
+        {
+            return None;
+        }
+
+        if self.lsb_first()
+        {
+            let lo = start_bit + offset;
+            Some((lo, cmp::min(stop_bit, lo + 7)))
+        }
+        else
+        {
+            let hi = stop_bit - offset;
+            Some((cmp::max(start_bit, hi.saturating_sub(7)), hi))
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the index into the bytes and the mask selecting <b>bit</b>.
+    fn locate_bit(&self, bit: usize) -> Result<(usize, u8), BitFieldError>
+    {
+        if self.bits <= bit
+        {
+            return Err(BitFieldError::InvalidIndex{start_bit: bit, stop_bit: bit,
+                                                   bit_len: self.bits});
+        }
+
+        let bit = self.offset + bit;
+        let shift = if self.lsb_first() { bit % 8 } else { 7 - bit % 8 };
+
+        Ok((self.physical_byte(bit / 8), 1u8 << shift))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Gets the value of a single bit.
+    pub fn get_bit(&self, bit: usize) -> Result<bool, BitFieldError>
+    {
+        let (byte, mask) = self.locate_bit(bit)?;
+
+        Ok(self.bytes[byte] & mask != 0)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Gets the value of an arbitrary length range of bits.
+    ///
+    /// <p>The bits are returned as a big-endian number in the smallest number
+    /// of bytes that can hold them, with any unused high bits of the first
+    /// byte set to zero.  This is the inverse of <b>insert_bytes</b>.</p>
+    #[cfg(feature = "alloc")]
+    pub fn retrieve_bytes(&self, start_bit: usize, stop_bit: usize) ->
+                                                 Result<Vec<u8>, BitFieldError>
+    {
+        if start_bit > stop_bit
+        {
+            return Err(BitFieldError::NegativeRange{start_bit, stop_bit});
+        }
+
+        if self.bits <= stop_bit
+        {
+            return Err(BitFieldError::InvalidIndex{start_bit, stop_bit,
+                                                   bit_len: self.bits});
+        }
+
+        let width = stop_bit - start_bit + 1;
+        let mut value = vec![0u8; width.div_ceil(8)];
+
+        for (n, byte) in value.iter_mut().rev().enumerate()
+        {
+            if let Some((start, stop)) = self.significant_chunk(n, start_bit, stop_bit)
+            {
+                *byte = self.retrieve_u64(start, stop)? as u8;
+            }
+        }
+
+        Ok(value)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Converts a range of bit numbers to the first and last bit of the
+    /// range, checking that it is not empty and lies within the object.
+    ///
+    /// <p>An unbounded end runs to the last bit of the object.  None of the
+    /// arithmetic can overflow, so <b>usize::MAX</b> bounds are reported as
+    /// errors rather than wrapping.</p>
+    pub fn bit_range<R: RangeBounds<usize>>(&self, range: R) ->
+                                          Result<(usize, usize), BitFieldError>
+    {
+        let start_bit = match range.start_bound()
+        {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) =>
+                start.checked_add(1).ok_or(BitFieldError::InvalidIndex{
+                    start_bit: start, stop_bit: start, bit_len: self.bits})?,
+            Bound::Unbounded => 0
+        };
+
+        let stop_bit = match range.end_bound()
+        {
+            Bound::Included(&stop) => stop,
+            Bound::Excluded(&stop) =>
+                stop.checked_sub(1).ok_or(BitFieldError::EmptyRange{start_bit})?,
+            Bound::Unbounded =>
+                self.bits.checked_sub(1).ok_or(BitFieldError::InvalidIndex{
+                    start_bit, stop_bit: start_bit, bit_len: self.bits})?
+        };
+
+        if start_bit > stop_bit
+        {
+            return Err(BitFieldError::NegativeRange{start_bit, stop_bit});
+        }
+
+        if self.bits <= stop_bit
+        {
+            return Err(BitFieldError::InvalidIndex{start_bit, stop_bit,
+                                                   bit_len: self.bits});
+        }
+
+        Ok((start_bit, stop_bit))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Gets the value of a range of bits as any <b>BitStore</b> type.
+    ///
+    /// <p>For example <b>bf.get::&lt;u16&gt;(4..16)</b> reads bits 4 through
+    /// 15, and <b>bf.get::&lt;i8&gt;(..8)</b> the sign extended first byte.</p>
+    pub fn get<T, R>(&self, range: R) -> Result<T, BitFieldError>
+        where T: BitStore, R: RangeBounds<usize>
+    {
+        let (start_bit, stop_bit) = self.bit_range(range)?;
+        let width = stop_bit - start_bit + 1;
+
+        if width > T::BITS
+        {
+            return Err(BitFieldError::ExceededDataRange{width, max_width: T::BITS});
+        }
+
+        Ok(T::from_raw(self.retrieve_u64(start_bit, stop_bit)?, width))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Gets the value of the specified range of bits.
+    pub fn retrieve_u64(&self, start_bit: usize, stop_bit: usize) ->
+                                                     Result<u64, BitFieldError>
+    {
+        if start_bit > stop_bit
+        {
+            return Err(BitFieldError::NegativeRange{start_bit, stop_bit});
+        }
+
+        if self.bits <= stop_bit
+        {
+            return Err(BitFieldError::InvalidIndex{start_bit, stop_bit,
+                                                   bit_len: self.bits});
+        }
+
+        if (stop_bit - start_bit + 1) > u64::BITS as usize
+        {
+            return Err(BitFieldError::ExceededDataRange{width: stop_bit - start_bit + 1,
+                                                        max_width: u64::BITS as usize});
+        }
+
+        // find out which bytes of the underlying buffer hold the range
+        let (start_bit, stop_bit) = (self.offset + start_bit, self.offset + stop_bit);
+        let start_byte = start_bit / 8;
+        let stop_byte  = stop_bit / 8;
+
+        let mut value = 0u64;
+
+        // loop through the bytes holding the value
+        for byte in start_byte..=stop_byte
+        {
+            // Find which bits of the byte and of the value are being read
+            let (bits_in_byte, byte_shift, value_shift) =
+                self.byte_shifts(byte, start_bit, stop_bit);
+
+            let byte_mask = BitSlice::get_mask(bits_in_byte);
+            let tmp = (self.bytes[self.physical_byte(byte)] >> byte_shift) & byte_mask;
+
+            value |= (tmp as u64) << value_shift;
+        }
+
+        Ok(value)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn retrieve_usize(&self, start_bit: usize, stop_bit: usize) ->
+                                                     Result<usize, BitFieldError>
+    {
+        self.get(start_bit..=stop_bit)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn retrieve_u32(&self, start_bit: usize, stop_bit: usize) ->
+                                                     Result<u32, BitFieldError>
+    {
+        self.get(start_bit..=stop_bit)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn retrieve_u16(&self, start_bit: usize, stop_bit: usize) ->
+                                                     Result<u16, BitFieldError>
+    {
+        self.get(start_bit..=stop_bit)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn retrieve_u8(&self, start_bit: usize, stop_bit: usize) ->
+                                                     Result<u8, BitFieldError>
+    {
+        self.get(start_bit..=stop_bit)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Gets the signed value of the specified range of bits, interpreting
+    /// them with the given <b>encoding</b>.  The most significant bit of the
+    /// range is the sign bit.  Negative zero is returned as zero.
+    pub fn retrieve_signed(&self,
+                           start_bit: usize,
+                           stop_bit: usize,
+                           encoding: SignedEncoding) -> Result<i64, BitFieldError>
+    {
+        let raw = self.retrieve_u64(start_bit, stop_bit)?;
+
+        let width = stop_bit - start_bit + 1;
+        let mask = u64::MAX >> (u64::BITS as usize - width);
+        let negative = (raw >> (width - 1)) & 1 == 1;
+
+        let value = match encoding
+        {
+            _ if !negative => raw as i64,
+            SignedEncoding::TwosComplement => (raw | !mask) as i64,
+            SignedEncoding::OnesComplement => -((!raw & mask) as i64),
+            SignedEncoding::SignMagnitude => -((raw & (mask >> 1)) as i64),
+        };
+
+        Ok(value)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Gets the two's complement value of the specified range of bits, sign
+    /// extended from the most significant bit of the range.
+    pub fn retrieve_i64(&self, start_bit: usize, stop_bit: usize) ->
+                                                     Result<i64, BitFieldError>
+    {
+        self.retrieve_signed(start_bit, stop_bit, SignedEncoding::TwosComplement)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn retrieve_i32(&self, start_bit: usize, stop_bit: usize) ->
+                                                     Result<i32, BitFieldError>
+    {
+        self.get(start_bit..=stop_bit)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn retrieve_i16(&self, start_bit: usize, stop_bit: usize) ->
+                                                     Result<i16, BitFieldError>
+    {
+        self.get(start_bit..=stop_bit)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn retrieve_i8(&self, start_bit: usize, stop_bit: usize) ->
+                                                     Result<i8, BitFieldError>
+    {
+        self.get(start_bit..=stop_bit)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Gets the value of the specified range of bits with its bytes stored in
+    /// the given <b>byte_order</b>.  See <b>insert_endian</b>.
+    pub fn retrieve_endian(&self,
+                           start_bit: usize,
+                           stop_bit: usize,
+                           byte_order: ByteOrder) -> Result<u64, BitFieldError>
+    {
+        self.check_range(start_bit, stop_bit)?;
+
+        let width = stop_bit - start_bit + 1;
+        let mut start = start_bit;
+        let mut value = 0u64;
+
+        for position in 0..width.div_ceil(8)
+        {
+            let (byte, bits) = BitSlice::endian_byte(position, width, byte_order);
+
+            value |= self.retrieve_u64(start, start + bits - 1)? << (8 * byte);
+            start += bits;
+        }
+
+        Ok(value)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Validates a range of up to 64 bits up front, so an invalid range is
+    /// never partially written and is reported before the value is checked.
+    fn check_range(&self, start_bit: usize, stop_bit: usize) ->
+                                                       Result<(), BitFieldError>
+    {
+        if start_bit > stop_bit
+        {
+            return Err(BitFieldError::NegativeRange{start_bit, stop_bit});
+        }
+
+        if self.bits <= stop_bit
+        {
+            return Err(BitFieldError::InvalidIndex{start_bit, stop_bit,
+                                                   bit_len: self.bits});
+        }
+
+        if (stop_bit - start_bit + 1) > u64::BITS as usize
+        {
+            return Err(BitFieldError::ExceededDataRange{width: stop_bit - start_bit + 1,
+                                                        max_width: u64::BITS as usize});
+        }
+
+        Ok(())
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// For the byte stored at <b>position</b> of a <b>width</b> bit value,
+    /// returns which byte of the value it is, counting from the least
+    /// significant, and how many bits it holds.
+    fn endian_byte(position: usize, width: usize, byte_order: ByteOrder) ->
+                                                                (usize, usize)
+    {
+        let count = width.div_ceil(8);
+
+        let byte = match byte_order
+        {
+            ByteOrder::BigEndian => count - 1 - position,
+            ByteOrder::LittleEndian => position,
+            ByteOrder::Pdp if count % 2 == 1 && position == 0 => count - 1,
+            ByteOrder::Pdp =>
+            {
+                // skip the lone most significant byte of an odd count
+                let position = position - count % 2;
+                let words = count / 2;
+
+                2 * (words - 1 - position / 2) + position % 2
+            }
+        };
+
+        let bits = if byte == count - 1 { width - 8 * byte } else { 8 };
+
+        (byte, bits)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Gets the IEEE-754 single precision value stored in the 32 bits
+    /// starting at <b>start_bit</b>.
+    pub fn retrieve_f32(&self, start_bit: usize) -> Result<f32, BitFieldError>
+    {
+        let stop_bit = start_bit.checked_add(31).ok_or(BitFieldError::InvalidIndex{
+            start_bit, stop_bit: usize::MAX, bit_len: self.bits})?;
+
+        self.retrieve_u32(start_bit, stop_bit).map(f32::from_bits)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Gets the IEEE-754 double precision value stored in the 64 bits
+    /// starting at <b>start_bit</b>.
+    pub fn retrieve_f64(&self, start_bit: usize) -> Result<f64, BitFieldError>
+    {
+        let stop_bit = start_bit.checked_add(63).ok_or(BitFieldError::InvalidIndex{
+            start_bit, stop_bit: usize::MAX, bit_len: self.bits})?;
+
+        self.retrieve_u64(start_bit, stop_bit).map(f64::from_bits)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Gets the real value of the specified range of bits, interpreting them
+    /// as a fixed-point number described by <b>format</b>.
+    pub fn retrieve_fixed(&self,
+                          start_bit: usize,
+                          stop_bit: usize,
+                          format: &FixedPoint) -> Result<f64, BitFieldError>
+    {
+        let raw =
+            if format.signed
+            {
+                self.retrieve_i64(start_bit, stop_bit)? as f64
+            }
+            else
+            {
+                self.retrieve_u64(start_bit, stop_bit)? as f64
+            };
+
+        Ok(raw * format.scale + format.offset)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the number of set bits in the object.
+    pub fn count_ones(&self) -> usize
+    {
+        self.count_ones_range(..).unwrap_or(0)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the number of clear bits in the object, not counting padding.
+    pub fn count_zeros(&self) -> usize
+    {
+        self.bits - self.count_ones()
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the number of clear bits above the most significant set bit,
+    /// which is the bit length when no bit is set.
+    pub fn leading_zeros(&self) -> usize
+    {
+        self.leading_zeros_range(..).unwrap_or(0)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the number of clear bits below the least significant set bit,
+    /// which is the bit length when no bit is set.
+    pub fn trailing_zeros(&self) -> usize
+    {
+        self.trailing_zeros_range(..).unwrap_or(0)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the lowest numbered set bit, if any.
+    pub fn first_set(&self) -> Option<usize>
+    {
+        self.first_set_range(..).ok().flatten()
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the highest numbered set bit, if any.
+    pub fn last_set(&self) -> Option<usize>
+    {
+        self.last_set_range(..).ok().flatten()
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns true when an odd number of bits are set.
+    pub fn parity(&self) -> bool
+    {
+        self.parity_range(..).unwrap_or(false)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the number of set bits in <b>range</b>.
+    ///
+    /// <p>The range is counted a 64 bit word at a time, which compiles to the
+    /// popcnt instruction where the target supports it.</p>
+    pub fn count_ones_range<R: RangeBounds<usize>>(&self, range: R) ->
+                                                    Result<usize, BitFieldError>
+    {
+        let (start_bit, stop_bit) = self.bit_range(range)?;
+        let (low, high) = self.numeric_range(start_bit, stop_bit);
+
+        Ok((low / 64..=high / 64).map(|w| (self.numeric_word(w) & BitSlice::word_mask(w, low, high))
+                                         .count_ones() as usize)
+                                 .sum())
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the number of clear bits in <b>range</b>.
+    pub fn count_zeros_range<R: RangeBounds<usize>>(&self, range: R) ->
+                                                    Result<usize, BitFieldError>
+    {
+        let (start_bit, stop_bit) = self.bit_range(range)?;
+
+        Ok(stop_bit - start_bit + 1 - self.count_ones_range(start_bit..=stop_bit)?)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the number of clear bits in <b>range</b> above its most
+    /// significant set bit.
+    pub fn leading_zeros_range<R: RangeBounds<usize>>(&self, range: R) ->
+                                                    Result<usize, BitFieldError>
+    {
+        let (start_bit, stop_bit) = self.bit_range(range)?;
+        let (low, high) = self.numeric_range(start_bit, stop_bit);
+
+        Ok(self.highest_matching(low, high, true).map_or(high - low + 1, |p| high - p))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the number of clear bits in <b>range</b> below its least
+    /// significant set bit.
+    pub fn trailing_zeros_range<R: RangeBounds<usize>>(&self, range: R) ->
+                                                    Result<usize, BitFieldError>
+    {
+        let (start_bit, stop_bit) = self.bit_range(range)?;
+        let (low, high) = self.numeric_range(start_bit, stop_bit);
+
+        Ok(self.lowest_matching(low, high, true).map_or(high - low + 1, |p| p - low))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the lowest numbered set bit in <b>range</b>, if any.
+    pub fn first_set_range<R: RangeBounds<usize>>(&self, range: R) ->
+                                            Result<Option<usize>, BitFieldError>
+    {
+        let (start_bit, stop_bit) = self.bit_range(range)?;
+
+        Ok(self.first_matching(start_bit, stop_bit, true))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the highest numbered set bit in <b>range</b>, if any.
+    pub fn last_set_range<R: RangeBounds<usize>>(&self, range: R) ->
+                                            Result<Option<usize>, BitFieldError>
+    {
+        let (start_bit, stop_bit) = self.bit_range(range)?;
+
+        Ok(self.last_matching(start_bit, stop_bit, true))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns true when an odd number of bits in <b>range</b> are set.
+    pub fn parity_range<R: RangeBounds<usize>>(&self, range: R) ->
+                                                     Result<bool, BitFieldError>
+    {
+        let (start_bit, stop_bit) = self.bit_range(range)?;
+        let (low, high) = self.numeric_range(start_bit, stop_bit);
+        let folded = (low / 64..=high / 64).fold(0, |acc, w| acc ^ (self.numeric_word(w) &
+                                                  BitSlice::word_mask(w, low, high)));
+
+        Ok(folded.count_ones() % 2 == 1)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Converts a bit number to its position in the underlying bytes read as
+    /// one number, counting from the least significant bit.
+    ///
+    /// <p>LSB0 bit numbers already count from the least significant bit of
+    /// their big (<b>Lsb0</b>) or little (<b>Lsb0Words</b>) endian number.
+    /// MSB0 bit numbers count from the most significant bit instead.</p>
+    fn numeric_bit(&self, bit: usize) -> usize
+    {
+        let bit = self.offset + bit;
+
+        if self.lsb_first()
+        {
+            bit
+        }
+        else
+        {
+            self.bytes.len() * 8 - 1 - bit
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Converts a numeric position back to a bit number.  See
+    /// <b>numeric_bit</b>.
+    fn numeric_to_bit(&self, position: usize) -> usize
+    {
+        if self.lsb_first()
+        {
+            position - self.offset
+        }
+        else
+        {
+            self.bytes.len() * 8 - 1 - position - self.offset
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the lowest and highest numeric positions of a bit range.
+    fn numeric_range(&self, start_bit: usize, stop_bit: usize) -> (usize, usize)
+    {
+        let (a, b) = (self.numeric_bit(start_bit), self.numeric_bit(stop_bit));
+
+        (cmp::min(a, b), cmp::max(a, b))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns numeric bits <b>64 * word</b> upwards as a u64, zero filled
+    /// past the end of the bytes.
+    fn numeric_word(&self, word: usize) -> u64
+    {
+        let mut buffer = [0u8; 8];
+        let len = self.bytes.len();
+
+        match self.order
+        {
+            BitOrder::Lsb0Words(_) =>
+            {
+                let start = word * 8;
+                let stop = cmp::min(start + 8, len);
+
+                buffer[..stop - start].copy_from_slice(&self.bytes[start..stop]);
+                u64::from_le_bytes(buffer)
+            },
+            _ =>
+            {
+                let stop = len - word * 8;
+                let start = stop.saturating_sub(8);
+
+                buffer[8 - (stop - start)..].copy_from_slice(&self.bytes[start..stop]);
+                u64::from_be_bytes(buffer)
+            }
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the mask of the numeric bits <b>low</b> to <b>high</b> that
+    /// fall within <b>word</b>.
+    fn word_mask(word: usize, low: usize, high: usize) -> u64
+    {
+        let first = cmp::max(low, word * 64) - word * 64;
+        let last = cmp::min(high, word * 64 + 63) - word * 64;
+
+        (u64::MAX << first) & (u64::MAX >> (63 - last))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns an iterator over every bit of the object, from bit 0.
+    pub fn iter(&self) -> Iter<'a>
+    {
+        Iter { field: *self, front: 0, back: self.bits }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns an iterator over the numbers of the set bits, lowest first.
+    /// Runs of clear bits are skipped a word at a time.
+    pub fn iter_ones(&self) -> BitIndices<'a>
+    {
+        BitIndices { field: *self, front: 0, back: self.bits, value: true }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns an iterator over the numbers of the clear bits, lowest first.
+    pub fn iter_zeros(&self) -> BitIndices<'a>
+    {
+        BitIndices { field: *self, front: 0, back: self.bits, value: false }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns an iterator over consecutive <b>width</b> bit values, read as
+    /// by <b>retrieve_u64</b>.  The last value is narrower when the bit
+    /// length is not a multiple of <b>width</b>.
+    ///
+    /// <p>Panics if <b>width</b> is 0 or more than 64.</p>
+    pub fn chunks(&self, width: usize) -> Chunks<'a>
+    {
+        assert!(width > 0 && width <= u64::BITS as usize, "Chunk width must be 1 to 64 bits");
+
+        Chunks { field: *self, width, front: 0, back: self.bits.div_ceil(width) }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns an iterator over every <b>width</b> bit value, starting at
+    /// each bit in turn, read as by <b>retrieve_u64</b>.
+    ///
+    /// <p>Panics if <b>width</b> is 0 or more than 64.</p>
+    pub fn windows(&self, width: usize) -> Windows<'a>
+    {
+        assert!(width > 0 && width <= u64::BITS as usize, "Window width must be 1 to 64 bits");
+
+        Windows { field: *self, width, front: 0, back: (self.bits + 1).saturating_sub(width) }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the lowest bit at which every bit of <b>pattern</b> matches,
+    /// bit 0 of the pattern lining up with the returned bit.  The two objects
+    /// may have different bit orders.  An empty pattern is never found.
+    pub fn find_pattern<'p>(&self, pattern: impl Into<BitSlice<'p>>) -> Option<usize>
+    {
+        self.find_pattern_within(pattern, 0)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the highest bit at which <b>pattern</b> matches.  See
+    /// <b>find_pattern</b>.
+    pub fn rfind_pattern<'p>(&self, pattern: impl Into<BitSlice<'p>>) -> Option<usize>
+    {
+        self.rfind_pattern_within(pattern, 0)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns every bit at which <b>pattern</b> matches, lowest first,
+    /// including overlapping matches.  See <b>find_pattern</b>.
+    #[cfg(feature = "alloc")]
+    pub fn find_all_patterns<'p>(&self, pattern: impl Into<BitSlice<'p>>) -> Vec<usize>
+    {
+        self.find_all_patterns_within(pattern, 0)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the lowest bit at which <b>pattern</b> matches with at most
+    /// <b>max_errors</b> bits different.
+    pub fn find_pattern_within<'p>(&self,
+                                   pattern: impl Into<BitSlice<'p>>,
+                                   max_errors: usize) -> Option<usize>
+    {
+        let mut found = None;
+
+        self.scan_pattern(&pattern.into(), max_errors, false, |offset|
+        {
+            found = Some(offset);
+            false
+        });
+
+        found
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the highest bit at which <b>pattern</b> matches with at most
+    /// <b>max_errors</b> bits different.
+    pub fn rfind_pattern_within<'p>(&self,
+                                    pattern: impl Into<BitSlice<'p>>,
+                                        max_errors: usize) -> Option<usize>
+    {
+        let mut found = None;
+
+        self.scan_pattern(&pattern.into(), max_errors, true, |offset|
+        {
+            found = Some(offset);
+            false
+        });
+
+        found
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns every bit at which <b>pattern</b> matches with at most
+    /// <b>max_errors</b> bits different, lowest first.
+    #[cfg(feature = "alloc")]
+    pub fn find_all_patterns_within<'p>(&self,
+                                        pattern: impl Into<BitSlice<'p>>,
+                                    max_errors: usize) -> Vec<usize>
+    {
+        let mut found = Vec::new();
+
+        self.scan_pattern(&pattern.into(), max_errors, false, |offset|
+        {
+            found.push(offset);
+            true
+        });
+
+        found
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Calls <b>visit</b> with each bit at which <b>pattern</b> matches with
+    /// at most <b>max_errors</b> bits different, until it returns false.
+    ///
+    /// <p>The object is read once, 64 bits at a time, each bit being shifted
+    /// into a register holding the last 64 bits seen.  The register is
+    /// compared with the first 64 bits of the pattern by counting the ones in
+    /// their XOR, and only when that leaves errors to spare are any later
+    /// pattern bits compared.</p>
+    fn scan_pattern<F>(&self, pattern: &BitSlice, max_errors: usize, reverse: bool, mut visit: F)
+        where F: FnMut(usize) -> bool
+    {
+        if pattern.bits == 0 || pattern.bits > self.bits
+        {
+            return;
+        }
+
+        let head = cmp::min(pattern.bits, u64::BITS as usize);
+        let mask = u64::MAX >> (64 - head);
+        let target = pattern.stream_chunk(0, head);
+        let last_offset = self.bits - pattern.bits;
+        let mut window = 0u64;
+
+        for chunk in BitSlice::directed(self.bits.div_ceil(64), reverse)
+        {
+            let start_bit = chunk * 64;
+            let count = cmp::min(64, self.bits - start_bit);
+            let word = self.stream_chunk(start_bit, count);
+
+            for k in BitSlice::directed(count, reverse)
+            {
+                let bit = (word >> (count - 1 - k)) & 1;
+                let offset;
+
+                if reverse
+                {
+                    // bits enter at the top, so the window starts here
+                    window = (window >> 1) | (bit << (head - 1));
+                    offset = start_bit + k;
+
+                    if offset > last_offset
+                    {
+                        continue;
+                    }
+                }
+                else
+                {
+                    // bits enter at the bottom, so the window ends here
+                    window = (window << 1) | bit;
+
+                    if start_bit + k + 1 < head
+                    {
+                        continue;
+                    }
+
+                    offset = start_bit + k + 1 - head;
+
+                    if offset > last_offset
+                    {
+                        return;
+                    }
+                }
+
+                let errors = ((window ^ target) & mask).count_ones() as usize;
+
+                if errors <= max_errors &&
+                   self.pattern_tail_within(pattern, offset, max_errors - errors) &&
+                   !visit(offset)
+                {
+                    return;
+                }
+            }
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Checks that the pattern bits after the first 64 differ from the
+    /// object at <b>offset</b> in at most <b>max_errors</b> places.
+    fn pattern_tail_within(&self, pattern: &BitSlice, offset: usize, max_errors: usize) -> bool
+    {
+        let mut errors = 0;
+        let mut done = u64::BITS as usize;
+
+        while done < pattern.bits
+        {
+            let count = cmp::min(64, pattern.bits - done);
+            let differ = self.stream_chunk(offset + done, count) ^ pattern.stream_chunk(done, count);
+
+            errors += differ.count_ones() as usize;
+            if errors > max_errors
+            {
+                return false;
+            }
+
+            done += count;
+        }
+
+        true
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns <b>count</b> bits from <b>start_bit</b> with the lowest
+    /// numbered bit the most significant, whatever the bit order.  The range
+    /// must be valid.
+    fn stream_chunk(&self, start_bit: usize, count: usize) -> u64
+    {
+        let value = self.retrieve_u64(start_bit, start_bit + count - 1)
+                        .expect("Stream chunk is within the object");
+
+        if self.lsb_first()
+        {
+            value.reverse_bits() >> (64 - count)
+        }
+        else
+        {
+            value
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns 0 to <b>count</b> - 1 in increasing or, when <b>reverse</b>,
+    /// decreasing order.
+    fn directed(count: usize, reverse: bool) -> impl Iterator<Item = usize>
+    {
+        (0..count).map(move |i| if reverse { count - 1 - i } else { i })
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the lowest numbered bit from <b>start_bit</b> to
+    /// <b>stop_bit</b> equal to <b>value</b>.  The range must be valid.
+    fn first_matching(&self, start_bit: usize, stop_bit: usize, value: bool) -> Option<usize>
+    {
+        let (low, high) = self.numeric_range(start_bit, stop_bit);
+        let found = if self.lsb_first() { self.lowest_matching(low, high, value) }
+                    else { self.highest_matching(low, high, value) };
+
+        found.map(|p| self.numeric_to_bit(p))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the highest numbered bit from <b>start_bit</b> to
+    /// <b>stop_bit</b> equal to <b>value</b>.  The range must be valid.
+    fn last_matching(&self, start_bit: usize, stop_bit: usize, value: bool) -> Option<usize>
+    {
+        let (low, high) = self.numeric_range(start_bit, stop_bit);
+        let found = if self.lsb_first() { self.highest_matching(low, high, value) }
+                    else { self.lowest_matching(low, high, value) };
+
+        found.map(|p| self.numeric_to_bit(p))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns numeric bits <b>64 * word</b> upwards, inverted when looking
+    /// for clear bits, masked to the numeric bits <b>low</b> to <b>high</b>.
+    fn matching_word(&self, word: usize, low: usize, high: usize, value: bool) -> u64
+    {
+        let bits = if value { self.numeric_word(word) } else { !self.numeric_word(word) };
+
+        bits & BitSlice::word_mask(word, low, high)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the lowest numeric position from <b>low</b> to <b>high</b>
+    /// equal to <b>value</b>.
+    fn lowest_matching(&self, low: usize, high: usize, value: bool) -> Option<usize>
+    {
+        (low / 64..=high / 64).find_map(|w|
+        {
+            let word = self.matching_word(w, low, high, value);
+
+            (word != 0).then(|| w * 64 + word.trailing_zeros() as usize)
+        })
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the highest numeric position from <b>low</b> to <b>high</b>
+    /// equal to <b>value</b>.
+    fn highest_matching(&self, low: usize, high: usize, value: bool) -> Option<usize>
+    {
+        (low / 64..=high / 64).rev().find_map(|w|
+        {
+            let word = self.matching_word(w, low, high, value);
+
+            (word != 0).then(|| w * 64 + 63 - word.leading_zeros() as usize)
+        })
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the bits holding the <b>digit</b>th digit, counting from the
+    /// least significant, of the view read as a number in base 2 to the
+    /// power <b>digit_bits</b>.  The most significant digit may be short.
+    pub(crate) fn digit_range(&self, digit: usize, digit_bits: usize) -> (usize, usize)
+    {
+        let low = digit * digit_bits;
+        let high = cmp::min(low + digit_bits, self.bits) - 1;
+
+        if self.lsb_first()
+        {
+            (low, high)
+        }
+        else
+        {
+            (self.bits - 1 - high, self.bits - 1 - low)
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Writes the view as a number in base 2 to the power <b>digit_bits</b>,
+    /// for the formatting traits.
+    ///
+    /// <p>The number is read as <b>retrieve_u64</b> reads a range, so for
+    /// MSB0 bit 0 is the most significant bit and otherwise the highest
+    /// numbered bit is.  Exactly enough digits for <b>bit_len()</b> bits are
+    /// written, the first covering the bits left over when the length is not
+    /// a multiple of <b>digit_bits</b>, and none for an empty view.  The
+    /// <b>#</b> flag adds <b>prefix</b>, width, fill, alignment and
+    /// <b>0</b> pad as for integers, and <b>+</b> separates groups of
+    /// digits from the least significant end with <b>_</b>.</p>
+    pub(crate) fn fmt_radix(&self,
+                            fmt: &mut fmt::Formatter,
+                            digit_bits: usize,
+                            prefix: &str,
+                            upper: bool) -> fmt::Result
+    {
+        let digits = self.bits.div_ceil(digit_bits);
+        let group = if digit_bits == 1 { 8 } else { 4 };
+        let grouped = fmt.sign_plus();
+        let prefix = if fmt.alternate() { prefix } else { "" };
+        let separators = if grouped && digits > 0 { (digits - 1) / group } else { 0 };
+        let padding = fmt.width().unwrap_or(0)
+                         .saturating_sub(prefix.len() + digits + separators);
+
+        let (before, after, fill) = if fmt.sign_aware_zero_pad()
+        {
+            (0, 0, '0')
+        }
+        else
+        {
+            match fmt.align()
+            {
+                Some(fmt::Alignment::Left) => (0, padding, fmt.fill()),
+                Some(fmt::Alignment::Center) =>
+                    (padding / 2, padding - padding / 2, fmt.fill()),
+                _ => (padding, 0, fmt.fill())
+            }
+        };
+
+        for _ in 0..before
+        {
+            fmt.write_char(fill)?;
+        }
+
+        fmt.write_str(prefix)?;
+
+        if fmt.sign_aware_zero_pad()
+        {
+            for _ in 0..padding
+            {
+                fmt.write_char('0')?;
+            }
+        }
+
+        for digit in (0..digits).rev()
+        {
+            let (start_bit, stop_bit) = self.digit_range(digit, digit_bits);
+            let value = self.retrieve_u64(start_bit, stop_bit).map_err(|_| fmt::Error)?;
+            let c = char::from_digit(value as u32, 1 << digit_bits).ok_or(fmt::Error)?;
+
+            fmt.write_char(if upper { c.to_ascii_uppercase() } else { c })?;
+
+            if grouped && digit != 0 && digit % group == 0
+            {
+                fmt.write_char('_')?;
+            }
+        }
+
+        for _ in 0..after
+        {
+            fmt.write_char(fill)?;
+        }
+
+        Ok(())
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl<'a> BitSliceMut<'a>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    /// Creates an MSB0 view of every bit of <b>bytes</b>.
+    pub fn new(bytes: &'a mut [u8]) -> BitSliceMut<'a>
+    {
+        let bits = bytes.len() * 8;
+
+        BitSliceMut::from_parts(bytes, BitOrder::Msb0, 0, bits)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub(crate) fn from_parts(bytes: &'a mut [u8], order: BitOrder, offset: usize, bits: usize) ->
+                                                                      BitSliceMut<'a>
+    {
+        BitSliceMut{bytes, order, offset, bits, overflow: None}
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub(crate) fn with_overflow(mut self, overflow: Option<OverflowPolicy>) -> BitSliceMut<'a>
+    {
+        self.overflow = overflow;
+        self
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the view with every insert that is not given a policy
+    /// handling values that do not fit as <b>overflow</b> directs.  Views
+    /// of a BitField start with its policy.
+    pub fn with_overflow_policy(self, overflow: OverflowPolicy) -> BitSliceMut<'a>
+    {
+        self.with_overflow(Some(overflow))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn overflow_policy(&self) -> Option<OverflowPolicy>
+    {
+        self.overflow
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the view using the given bit numbering <b>order</b> for the
+    /// bytes it borrows.
+    pub fn with_bit_order(mut self, order: BitOrder) -> BitSliceMut<'a>
+    {
+        if let BitOrder::Lsb0Words(0) = order
+        {
+            panic!("Word width must be at least one byte");
+        }
+
+        self.order = order;
+        self
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn bit_order(&self) -> BitOrder
+    {
+        self.order
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn bit_len(&self) -> usize
+    {
+        self.bits
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn is_empty(&self) -> bool
+    {
+        self.bits == 0
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns a read only view of the same bits.
+    pub fn as_bitslice(&self) -> BitSlice<'_>
+    {
+        BitSlice::from_parts(self.bytes, self.order, self.offset, self.bits)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns a mutable view of the bits in <b>range</b>, numbered from 0,
+    /// without copying them.
+    pub fn slice_mut<R: RangeBounds<usize>>(&mut self, range: R) ->
+                                             Result<BitSliceMut<'_>, BitFieldError>
+    {
+        let (start_bit, stop_bit) = self.bit_range(range)?;
+
+        Ok(BitSliceMut::from_parts(self.bytes, self.order, self.offset + start_bit,
+                                   stop_bit - start_bit + 1).with_overflow(self.overflow))
+    }
+
+    bit_slice_reads!();
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Sets a single bit to 1.
+    pub fn set_bit(&mut self, bit: usize) -> Result<(), BitFieldError>
+    {
+        let (byte, mask) = self.as_bitslice().locate_bit(bit)?;
+
+        self.bytes[byte] |= mask;
+        Ok(())
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Clears a single bit to 0.
+    pub fn clear_bit(&mut self, bit: usize) -> Result<(), BitFieldError>
+    {
+        let (byte, mask) = self.as_bitslice().locate_bit(bit)?;
+
+        self.bytes[byte] &= !mask;
+        Ok(())
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Inverts a single bit.
+    pub fn toggle_bit(&mut self, bit: usize) -> Result<(), BitFieldError>
+    {
+        let (byte, mask) = self.as_bitslice().locate_bit(bit)?;
+
+        self.bytes[byte] ^= mask;
+        Ok(())
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Sets a single bit to 1 if <b>value</b> is true, otherwise 0.
+    pub fn assign_bit(&mut self, bit: usize, value: bool) -> Result<(), BitFieldError>
+    {
+        if value
+        {
+            self.set_bit(bit)
+        }
+        else
+        {
+            self.clear_bit(bit)
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Applies <b>op</b> to every byte holding part of the range with a mask
+    /// of the bits of the byte inside the range.
+    fn apply_range<F>(&mut self, start_bit: usize, stop_bit: usize, op: F) ->
+                                                       Result<(), BitFieldError>
+        where F: Fn(u8, u8) -> u8
+    {
+        if start_bit > stop_bit
+        {
+            return Err(BitFieldError::NegativeRange{start_bit, stop_bit});
+        }
+
+        if self.bits <= stop_bit
+        {
+            return Err(BitFieldError::InvalidIndex{start_bit, stop_bit,
+                                                   bit_len: self.bits});
+        }
+
+        let (start_bit, stop_bit) = (self.offset + start_bit, self.offset + stop_bit);
+
+        for byte in (start_bit / 8)..=(stop_bit / 8)
+        {
+            let (bits_in_byte, byte_shift, _) =
+                self.as_bitslice().byte_shifts(byte, start_bit, stop_bit);
+            let mask = BitSlice::get_mask(bits_in_byte) << byte_shift;
+            let current_byte = self.as_bitslice().physical_byte(byte);
+
+            self.bytes[current_byte] = op(self.bytes[current_byte], mask);
+        }
+
+        Ok(())
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Sets every bit of the range to 1.
+    pub fn set_range(&mut self, start_bit: usize, stop_bit: usize) ->
+                                                       Result<(), BitFieldError>
+    {
+        self.apply_range(start_bit, stop_bit, |b, mask| b | mask)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Clears every bit of the range to 0.
+    pub fn clear_range(&mut self, start_bit: usize, stop_bit: usize) ->
+                                                       Result<(), BitFieldError>
+    {
+        self.apply_range(start_bit, stop_bit, |b, mask| b & !mask)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Inverts every bit of the range.
+    pub fn toggle_range(&mut self, start_bit: usize, stop_bit: usize) ->
+                                                       Result<(), BitFieldError>
+    {
+        self.apply_range(start_bit, stop_bit, |b, mask| b ^ mask)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Inserts an arbitrary length byte slice into the object at the
+    /// specified bit locations
+    ///
+    /// <p>The <b>value</b> is treated as a big-endian number, so the last
+    /// <b>stop_bit</b> - <b>start_bit</b> + 1 bits of <b>value</b> are
+    /// stored.  For example, inserting [0x12, 0x34] into bits 0 through 11
+    /// stores 0x234.  The range may not be wider than <b>value</b>.  The
+    /// number is laid out in the range following the object's bit order.
+    /// Set bits of <b>value</b> above the range are an overflow, handled
+    /// as the object's policy directs, truncating by default.</p>
+    pub fn insert_bytes(&mut self, value: &[u8], start_bit: usize, stop_bit: usize) ->
+                                                       Result<(), BitFieldError>
+    {
+        if start_bit > stop_bit
+        {
+            return Err(BitFieldError::NegativeRange{start_bit, stop_bit});
+        }
+
+        if self.bits <= stop_bit
+        {
+            return Err(BitFieldError::InvalidIndex{start_bit, stop_bit,
+                                                   bit_len: self.bits});
+        }
+
+        let width = stop_bit - start_bit + 1;
+
+        if width > value.len() * 8
+        {
+            return Err(BitFieldError::ExceededDataRange{width,
+                                                        max_width: value.len() * 8});
+        }
+
+        // the bits of the value above the range must be clear to fit it
+        let dropped = value.len() * 8 - width;
+        let fits = value[..dropped / 8].iter().all(|&b| b == 0) &&
+                   (dropped.is_multiple_of(8) || value[dropped / 8] >> (8 - dropped % 8) == 0);
+
+        if !fits
+        {
+            match self.overflow.unwrap_or(OverflowPolicy::Truncate)
+            {
+                OverflowPolicy::Truncate => {}
+                OverflowPolicy::Checked => return Err(BitFieldError::Overflow{width}),
+                OverflowPolicy::Saturate => return self.set_range(start_bit, stop_bit)
+            }
+        }
+
+        // walk backwards through the value, one byte at a time, placing each
+        // byte in the next most significant part of the range
+        for (n, byte) in value.iter().rev().enumerate()
+        {
+            match self.as_bitslice().significant_chunk(n, start_bit, stop_bit)
+            {
+                Some((start, stop)) => self.write_u64(*byte as u64, start, stop)?,
+                None => break
+            }
+        }
+
+        Ok(())
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Stores any <b>BitStore</b> value into a range of bits, for example
+    /// <b>bf.set(4..=15, 0xabcu16)</b>.
+    pub fn set<T, R>(&mut self, range: R, value: T) -> Result<(), BitFieldError>
+        where T: BitStore, R: RangeBounds<usize>
+    {
+        self.set_with(range, value, self.overflow.unwrap_or(T::OVERFLOW))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Stores any <b>BitStore</b> value into a range of bits, handling a
+    /// value that does not fit as <b>overflow</b> directs.
+    pub fn set_with<T, R>(&mut self, range: R, value: T, overflow: OverflowPolicy) ->
+                                                       Result<(), BitFieldError>
+        where T: BitStore, R: RangeBounds<usize>
+    {
+        let (start_bit, stop_bit) = self.bit_range(range)?;
+        let width = stop_bit - start_bit + 1;
+
+        if width > T::BITS
+        {
+            return Err(BitFieldError::ExceededDataRange{width, max_width: T::BITS});
+        }
+
+        self.write_u64(value.to_raw(width, overflow)?, start_bit, stop_bit)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Inserts the value into the object at the specified bit locations
+    ///
+    /// <p>The passed in <b>value</b> will be masked against the
+    /// range specified by <b>start_bit</b> and <b>stop_bit</b>.  For example,
+    /// if <b>value</b> = 6 (binary 0110), and <b>start_bit</b> = 5, and
+    /// <b>stop_bit</b> = 6, the <b>value</b> will be masked to 2 (binary 10).
+    /// Setting an <b>OverflowPolicy</b> on the object or view changes
+    /// this.</p>
+    pub fn insert_u64(&mut self, value: u64, start_bit: usize, stop_bit: usize) ->
+                                                       Result<(), BitFieldError>
+    {
+        let overflow = self.overflow.unwrap_or(OverflowPolicy::Truncate);
+
+        self.insert_u64_with(value, start_bit, stop_bit, overflow)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Inserts the value into the object at the specified bit locations,
+    /// handling a value wider than the range as <b>overflow</b> directs.
+    pub fn insert_u64_with(&mut self,
+                           value: u64,
+                           start_bit: usize,
+                           stop_bit: usize,
+                           overflow: OverflowPolicy) -> Result<(), BitFieldError>
+    {
+        self.as_bitslice().check_range(start_bit, stop_bit)?;
+
+        let value = overflow.fit_unsigned(value as i128, stop_bit - start_bit + 1)?;
+
+        self.write_u64(value, start_bit, stop_bit)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Writes the low bits of <b>value</b> to the range, for the inserts
+    /// that have already fitted the value to it.
+    fn write_u64(&mut self, value: u64, start_bit: usize, stop_bit: usize) ->
+                                                       Result<(), BitFieldError>
+    {
+        if start_bit > stop_bit
+        {
+            return Err(BitFieldError::NegativeRange{start_bit, stop_bit});
+        }
+
+        if self.bits <= stop_bit
+        {
+            return Err(BitFieldError::InvalidIndex{start_bit, stop_bit,
+                                                   bit_len: self.bits});
+        }
+
+        if (stop_bit - start_bit + 1) > u64::BITS as usize
+        {
+            return Err(BitFieldError::ExceededDataRange{width: stop_bit - start_bit + 1,
+                                                        max_width: u64::BITS as usize});
+        }
+
+        // find out which bytes of the underlying buffer hold the range
+        let (start_bit, stop_bit) = (self.offset + start_bit, self.offset + stop_bit);
+        let start_byte = start_bit / 8;
+        let stop_byte  = stop_bit / 8;
+
+        // loop through the bytes to change the value of
+        for byte in start_byte..=stop_byte
+        {
+            // Find which bits of the byte and of the value are being changed
+            let (bits_in_byte, byte_shift, value_shift) =
+                self.as_bitslice().byte_shifts(byte, start_bit, stop_bit);
+
+            // Find mask and value for this byte
+            let byte_mask = BitSlice::get_mask(bits_in_byte);
+            let byte_value = (value >> value_shift) as u8 & byte_mask;
+
+            // stores the value by clearing the selected portion of the byte
+            // with negation of the mask then inserts the value in the byte.
+            let current_byte = self.as_bitslice().physical_byte(byte);
+            let b = &mut self.bytes[current_byte];
+            *b = *b & !(byte_mask << byte_shift) | (byte_value << byte_shift);
+        }
+
+        Ok(())
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn insert_usize(&mut self, value: usize, start_bit: usize, stop_bit: usize) ->
+                                                       Result<(), BitFieldError>
+    {
+        self.set(start_bit..=stop_bit, value)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn insert_u32(&mut self, value: u32, start_bit: usize, stop_bit: usize) ->
+                                                       Result<(), BitFieldError>
+    {
+        self.set(start_bit..=stop_bit, value)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn insert_u16(&mut self, value: u16, start_bit: usize, stop_bit: usize) ->
+                                                       Result<(), BitFieldError>
+    {
+        self.set(start_bit..=stop_bit, value)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn insert_u8(&mut self, value: u8, start_bit: usize, stop_bit: usize) ->
+                                                       Result<(), BitFieldError>
+    {
+        self.set(start_bit..=stop_bit, value)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Inserts a signed value into the object at the specified bit locations
+    /// using the given <b>encoding</b>.
+    ///
+    /// <p>Unlike the unsigned inserts the value is not masked; if it can not
+    /// be represented in the range <b>BitFieldError::Overflow</b> is
+    /// returned, unless an <b>OverflowPolicy</b> is set on the object or
+    /// view.  For example, a 4 bit two's complement range holds -8 through
+    /// 7, while a 4 bit sign-magnitude range holds -7 through 7.</p>
+    pub fn insert_signed(&mut self,
+                         value: i64,
+                         start_bit: usize,
+                         stop_bit: usize,
+                         encoding: SignedEncoding) -> Result<(), BitFieldError>
+    {
+        let overflow = self.overflow.unwrap_or(OverflowPolicy::Checked);
+
+        self.insert_signed_with(value, start_bit, stop_bit, encoding, overflow)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Inserts a signed value using the given <b>encoding</b>, handling a
+    /// value the range can not represent as <b>overflow</b> directs.
+    pub fn insert_signed_with(&mut self,
+                              value: i64,
+                              start_bit: usize,
+                              stop_bit: usize,
+                              encoding: SignedEncoding,
+                              overflow: OverflowPolicy) -> Result<(), BitFieldError>
+    {
+        self.as_bitslice().check_range(start_bit, stop_bit)?;
+
+        let width = stop_bit - start_bit + 1;
+        let value = overflow.fit_signed(value as i128, width, encoding)?;
+
+        let raw = match encoding
+        {
+            SignedEncoding::TwosComplement => value as u64,
+            SignedEncoding::OnesComplement if value < 0 =>
+                !value.unsigned_abs(),
+            SignedEncoding::SignMagnitude if value < 0 =>
+                (1u64 << (width - 1)) | value.unsigned_abs(),
+            _ => value as u64
+        };
+
+        self.write_u64(raw, start_bit, stop_bit)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Inserts a two's complement value into the object at the specified bit
+    /// locations.  See <b>insert_signed</b>.
+    pub fn insert_i64(&mut self, value: i64, start_bit: usize, stop_bit: usize) ->
+                                                       Result<(), BitFieldError>
+    {
+        self.insert_signed(value, start_bit, stop_bit,
+                           SignedEncoding::TwosComplement)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn insert_i32(&mut self, value: i32, start_bit: usize, stop_bit: usize) ->
+                                                       Result<(), BitFieldError>
+    {
+        self.set(start_bit..=stop_bit, value)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn insert_i16(&mut self, value: i16, start_bit: usize, stop_bit: usize) ->
+                                                       Result<(), BitFieldError>
+    {
+        self.set(start_bit..=stop_bit, value)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn insert_i8(&mut self, value: i8, start_bit: usize, stop_bit: usize) ->
+                                                       Result<(), BitFieldError>
+    {
+        self.set(start_bit..=stop_bit, value)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Inserts the value into the object at the specified bit locations with
+    /// its bytes stored in the given <b>byte_order</b>
+    ///
+    /// <p>The value is masked to the range as with <b>insert_u64</b>.  The
+    /// bits of each byte are laid out following the object's bit order, so
+    /// <b>ByteOrder::BigEndian</b> with <b>BitOrder::Msb0</b> and
+    /// <b>ByteOrder::LittleEndian</b> with the LSB0 orders are the same as
+    /// <b>insert_u64</b>.</p>
+    pub fn insert_endian(&mut self,
+                         value: u64,
+                         start_bit: usize,
+                         stop_bit: usize,
+                         byte_order: ByteOrder) -> Result<(), BitFieldError>
+    {
+        self.as_bitslice().check_range(start_bit, stop_bit)?;
+
+        let width = stop_bit - start_bit + 1;
+        let overflow = self.overflow.unwrap_or(OverflowPolicy::Truncate);
+        let value = overflow.fit_unsigned(value as i128, width)?;
+        let mut start = start_bit;
+
+        for position in 0..width.div_ceil(8)
+        {
+            let (byte, bits) = BitSlice::endian_byte(position, width, byte_order);
+
+            self.write_u64(value >> (8 * byte), start, start + bits - 1)?;
+            start += bits;
+        }
+
+        Ok(())
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Inserts an IEEE-754 single precision value into the 32 bits starting
+    /// at <b>start_bit</b>.
+    pub fn insert_f32(&mut self, value: f32, start_bit: usize) ->
+                                                       Result<(), BitFieldError>
+    {
+        let stop_bit = start_bit.checked_add(31).ok_or(BitFieldError::InvalidIndex{
+            start_bit, stop_bit: usize::MAX, bit_len: self.bits})?;
+
+        self.write_u64(value.to_bits() as u64, start_bit, stop_bit)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Inserts an IEEE-754 double precision value into the 64 bits starting
+    /// at <b>start_bit</b>.
+    pub fn insert_f64(&mut self, value: f64, start_bit: usize) ->
+                                                       Result<(), BitFieldError>
+    {
+        let stop_bit = start_bit.checked_add(63).ok_or(BitFieldError::InvalidIndex{
+            start_bit, stop_bit: usize::MAX, bit_len: self.bits})?;
+
+        self.write_u64(value.to_bits(), start_bit, stop_bit)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Inserts a real value into the specified bit locations as a fixed-point
+    /// number described by <b>format</b>.
+    ///
+    /// <p>The value is converted to the raw integer using the format's
+    /// rounding mode.  Unless an <b>OverflowPolicy</b> is set on the object
+    /// or view, values beyond what the range can hold saturate to the
+    /// smallest or largest raw value; for example, 8.0 stored as Q3.12 in a
+    /// 16 bit range becomes 0x7fff (7.99976).  NaN can not be represented and
+    /// returns <b>BitFieldError::Overflow</b>.</p>
+    pub fn insert_fixed(&mut self,
+                        value: f64,
+                        start_bit: usize,
+                        stop_bit: usize,
+                        format: &FixedPoint) -> Result<(), BitFieldError>
+    {
+        let overflow = self.overflow.unwrap_or(OverflowPolicy::Saturate);
+
+        self.insert_fixed_with(value, start_bit, stop_bit, format, overflow)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Inserts a real value as a fixed-point number described by
+    /// <b>format</b>, handling a raw value the range can not hold as
+    /// <b>overflow</b> directs.  Infinities can only be saturated.
+    pub fn insert_fixed_with(&mut self,
+                             value: f64,
+                             start_bit: usize,
+                             stop_bit: usize,
+                             format: &FixedPoint,
+                             overflow: OverflowPolicy) -> Result<(), BitFieldError>
+    {
+        self.as_bitslice().check_range(start_bit, stop_bit)?;
+
+        let width = stop_bit - start_bit + 1;
+
+        if value.is_nan() ||
+           (value.is_infinite() && overflow != OverflowPolicy::Saturate)
+        {
+            return Err(BitFieldError::Overflow{width});
+        }
+
+        let scaled = (value - format.offset) / format.scale;
+
+        // float to integer casts saturate, so this is exact for every width
+        let raw = round(scaled, format.rounding) as i128;
+
+        if format.signed
+        {
+            let raw = overflow.fit_signed(raw, width, SignedEncoding::TwosComplement)?;
+
+            self.write_u64(raw as u64, start_bit, stop_bit)
+        }
+        else
+        {
+            let raw = overflow.fit_unsigned(raw, width)?;
+
+            self.write_u64(raw, start_bit, stop_bit)
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Rounds <b>value</b> to an integer as <b>rounding</b> directs.  The rounding
+// methods of f64 need std, so this works from the integer part instead.
+fn round(value: f64, rounding: Rounding) -> f64
+{
+    // every value this large is already an integer, as are the infinities
+    const INTEGRAL: f64 = (1u64 << f64::MANTISSA_DIGITS) as f64;
+
+    if !(value > -INTEGRAL && value < INTEGRAL)
+    {
+        return value;
+    }
+
+    let whole = value as i64;
+    let fraction = value - whole as f64;
+    let odd = whole % 2 != 0;
+
+    let whole = match rounding
+    {
+        Rounding::TowardZero => whole,
+        Rounding::Floor if fraction < 0.0 => whole - 1,
+        Rounding::Floor => whole,
+        Rounding::Ceil if fraction > 0.0 => whole + 1,
+        Rounding::Ceil => whole,
+        Rounding::Nearest if fraction >= 0.5 => whole + 1,
+        Rounding::Nearest if fraction <= -0.5 => whole - 1,
+        Rounding::Nearest => whole,
+        Rounding::NearestEven if fraction > 0.5 || (fraction == 0.5 && odd) => whole + 1,
+        Rounding::NearestEven if fraction < -0.5 || (fraction == -0.5 && odd) => whole - 1,
+        Rounding::NearestEven => whole,
+    };
+
+    whole as f64
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl fmt::Binary for BitSlice<'_>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result
+    {
+        self.fmt_radix(fmt, 1, "0b", false)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl fmt::Octal for BitSlice<'_>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result
+    {
+        self.fmt_radix(fmt, 3, "0o", false)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl fmt::LowerHex for BitSlice<'_>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result
+    {
+        self.fmt_radix(fmt, 4, "0x", false)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl fmt::UpperHex for BitSlice<'_>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result
+    {
+        self.fmt_radix(fmt, 4, "0x", true)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl<'a> From<&'a [u8]> for BitSlice<'a>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn from(bytes: &'a [u8]) -> BitSlice<'a>
+    {
+        BitSlice::new(bytes)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl<'a, S: BitStorage> From<&'a BitField<S>> for BitSlice<'a>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn from(field: &'a BitField<S>) -> BitSlice<'a>
+    {
+        field.as_bitslice()
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl<'a> From<&'a BitSliceMut<'_>> for BitSlice<'a>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn from(slice: &'a BitSliceMut<'_>) -> BitSlice<'a>
+    {
+        slice.as_bitslice()
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl<'a> IntoIterator for BitSlice<'a>
+{
+    type Item = bool;
+    type IntoIter = Iter<'a>;
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn into_iter(self) -> Iter<'a>
+    {
+        self.iter()
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// Iterator over the bits of a BitSlice or BitField, returned by
+/// <b>iter</b>.
+#[derive(Clone, Debug)]
+pub struct Iter<'a>
+{
+    field: BitSlice<'a>,
+    front: usize,
+    back: usize
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl Iterator for Iter<'_>
+{
+    type Item = bool;
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn next(&mut self) -> Option<bool>
+    {
+        if self.front == self.back
+        {
+            return None;
+        }
+
+        self.front += 1;
+        self.field.get_bit(self.front - 1).ok()
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn size_hint(&self) -> (usize, Option<usize>)
+    {
+        (self.back - self.front, Some(self.back - self.front))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl DoubleEndedIterator for Iter<'_>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn next_back(&mut self) -> Option<bool>
+    {
+        if self.front == self.back
+        {
+            return None;
+        }
+
+        self.back -= 1;
+        self.field.get_bit(self.back).ok()
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+impl FusedIterator for Iter<'_> {}
+
+///////////////////////////////////////////////////////////////////////////////
+/// Iterator over the numbers of the set or clear bits of a BitSlice or
+/// BitField, returned by <b>iter_ones</b> and <b>iter_zeros</b>.
+#[derive(Clone, Debug)]
+pub struct BitIndices<'a>
+{
+    field: BitSlice<'a>,
+    front: usize,
+    back: usize,
+    value: bool
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl Iterator for BitIndices<'_>
+{
+    type Item = usize;
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn next(&mut self) -> Option<usize>
+    {
+        if self.front == self.back
+        {
+            return None;
+        }
+
+        let found = self.field.first_matching(self.front, self.back - 1, self.value);
+
+        self.front = found.map_or(self.back, |bit| bit + 1);
+        found
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn size_hint(&self) -> (usize, Option<usize>)
+    {
+        (0, Some(self.back - self.front))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl DoubleEndedIterator for BitIndices<'_>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn next_back(&mut self) -> Option<usize>
+    {
+        if self.front == self.back
+        {
+            return None;
+        }
+
+        let found = self.field.last_matching(self.front, self.back - 1, self.value);
+
+        self.back = found.unwrap_or(self.front);
+        found
+    }
+}
+
+impl FusedIterator for BitIndices<'_> {}
+
+///////////////////////////////////////////////////////////////////////////////
+/// Iterator over fixed width values of a BitSlice or BitField, returned by
+/// <b>chunks</b>.
+#[derive(Clone, Debug)]
+pub struct Chunks<'a>
+{
+    field: BitSlice<'a>,
+    width: usize,
+    front: usize,
+    back: usize
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl Chunks<'_>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the value of chunk number <b>chunk</b>.
+    fn chunk(&self, chunk: usize) -> u64
+    {
+        let start_bit = chunk * self.width;
+        let stop_bit = cmp::min(start_bit + self.width, self.field.bits) - 1;
+
+        self.field.retrieve_u64(start_bit, stop_bit).expect("Chunk is within the object")
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl Iterator for Chunks<'_>
+{
+    type Item = u64;
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn next(&mut self) -> Option<u64>
+    {
+        if self.front == self.back
+        {
+            return None;
+        }
+
+        self.front += 1;
+        Some(self.chunk(self.front - 1))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn size_hint(&self) -> (usize, Option<usize>)
+    {
+        (self.back - self.front, Some(self.back - self.front))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl DoubleEndedIterator for Chunks<'_>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn next_back(&mut self) -> Option<u64>
+    {
+        if self.front == self.back
+        {
+            return None;
+        }
+
+        self.back -= 1;
+        Some(self.chunk(self.back))
+    }
+}
+
+impl ExactSizeIterator for Chunks<'_> {}
+impl FusedIterator for Chunks<'_> {}
+
+///////////////////////////////////////////////////////////////////////////////
+/// Iterator over overlapping fixed width values of a BitSlice or BitField,
+/// returned by <b>windows</b>.
+#[derive(Clone, Debug)]
+pub struct Windows<'a>
+{
+    field: BitSlice<'a>,
+    width: usize,
+    front: usize,
+    back: usize
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl Windows<'_>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the value of the window starting at <b>start_bit</b>.
+    fn window(&self, start_bit: usize) -> u64
+    {
+        self.field.retrieve_u64(start_bit, start_bit + self.width - 1)
+                  .expect("Window is within the object")
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl Iterator for Windows<'_>
+{
+    type Item = u64;
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn next(&mut self) -> Option<u64>
+    {
+        if self.front == self.back
+        {
+            return None;
+        }
+
+        self.front += 1;
+        Some(self.window(self.front - 1))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn size_hint(&self) -> (usize, Option<usize>)
+    {
+        (self.back - self.front, Some(self.back - self.front))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl DoubleEndedIterator for Windows<'_>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn next_back(&mut self) -> Option<u64>
+    {
+        if self.front == self.back
+        {
+            return None;
+        }
+
+        self.back -= 1;
+        Some(self.window(self.back))
+    }
+}
+
+impl ExactSizeIterator for Windows<'_> {}
+impl FusedIterator for Windows<'_> {}
@@ -0,0 +1,524 @@
+use core::cmp;
+use core::fmt::{self, Write};
+use core::ops::{Bound, RangeBounds};
+
+use crate::bitfield::{BitField, BitOrder};
+use crate::bitslice::BitSlice;
+use crate::storage::BitStorage;
+
+///////////////////////////////////////////////////////////////////////////////
+/// The base the offset of each row is written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OffsetBase
+{
+    Decimal,
+    Hex,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Column
+{
+    Binary,
+    Hex,
+    Ascii,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// Renders the bytes of a BitField as a table, one row of bytes per line,
+/// through <b>Display</b>.
+///
+/// <p>Each row starts with the offset of its first byte and has any of a
+/// binary, hex and ASCII column.  By default rows hold 4 bytes with 6 digit
+/// decimal offsets and binary and hex columns under a header:</p>
+///
+/// <pre>
+/// Offset  Binary                                Hex
+/// =======================================================
+/// 000000: 11111111 11111111 11111111 11111111   ffff ffff
+/// 000004: 11111---                              f8
+/// </pre>
+///
+/// <p>Padding bits beyond the length of the object are shown as '-'.  With
+/// <b>BitOrder::Lsb0Words</b> each word is shown most significant byte
+/// first, so the words read as numbers.  Highlighted bits are marked with
+/// '^' on a line under their row, as are the hex digits and characters of
+/// the bytes holding them.</p>
+#[derive(Clone, Copy, Debug)]
+pub struct HexDump<'a>
+{
+    bytes: &'a [u8],
+    view: BitSlice<'a>,
+    start: usize,
+    end: usize,
+    bytes_per_row: usize,
+    offset_base: OffsetBase,
+    offset_width: usize,
+    binary: bool,
+    hex: bool,
+    ascii: bool,
+    header: bool,
+    xxd: bool,
+    highlight: Option<(usize, usize)>
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl<'a> HexDump<'a>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    /// Creates a dump of every byte of <b>field</b> with the default
+    /// layout.
+    pub fn new<S: BitStorage>(field: &'a BitField<S>) -> HexDump<'a>
+    {
+        let bytes = field.as_slice();
+
+        HexDump{bytes,
+                view: field.as_bitslice(),
+                start: 0,
+                end: bytes.len(),
+                bytes_per_row: 4,
+                offset_base: OffsetBase::Decimal,
+                offset_width: 6,
+                binary: true,
+                hex: true,
+                ascii: false,
+                header: true,
+                xxd: false,
+                highlight: None}
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the dump laid out exactly as <b>xxd</b> prints the bytes:
+    /// 16 bytes per row, 8 digit hex offsets, hex and ASCII columns, no
+    /// header, and the bytes in storage order.
+    ///
+    /// <p>The other settings can still be changed afterwards, so
+    /// <b>with_bytes_per_row</b> matches <b>xxd -c</b>, <b>with_range</b>
+    /// matches <b>-s</b> and <b>-l</b>, and a binary column in place of
+    /// the hex one with 6 bytes per row matches <b>xxd -b</b>.  Padding
+    /// bits are shown as the zeros they hold.</p>
+    pub fn xxd(mut self) -> HexDump<'a>
+    {
+        self.bytes_per_row = 16;
+        self.offset_base = OffsetBase::Hex;
+        self.offset_width = 8;
+        self.binary = false;
+        self.hex = true;
+        self.ascii = true;
+        self.header = false;
+        self.xxd = true;
+        self
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn with_bytes_per_row(mut self, bytes_per_row: usize) -> HexDump<'a>
+    {
+        if bytes_per_row == 0
+        {
+            panic!("Rows must hold at least one byte");
+        }
+
+        self.bytes_per_row = bytes_per_row;
+        self
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the dump with offsets written in <b>base</b>, zero padded to
+    /// at least <b>width</b> digits.
+    pub fn with_offset(mut self, base: OffsetBase, width: usize) -> HexDump<'a>
+    {
+        self.offset_base = base;
+        self.offset_width = width;
+        self
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn with_binary(mut self, binary: bool) -> HexDump<'a>
+    {
+        self.binary = binary;
+        self
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn with_hex(mut self, hex: bool) -> HexDump<'a>
+    {
+        self.hex = hex;
+        self
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the dump with an ASCII column, showing bytes outside the
+    /// printable range as '.'.
+    pub fn with_ascii(mut self, ascii: bool) -> HexDump<'a>
+    {
+        self.ascii = ascii;
+        self
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn with_header(mut self, header: bool) -> HexDump<'a>
+    {
+        self.header = header;
+        self
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the dump of only the bytes in <b>range</b>, each row labelled
+    /// with the offset of its first byte in the object.  The range is
+    /// clipped to the bytes of the object.
+    pub fn with_range<R: RangeBounds<usize>>(mut self, range: R) -> HexDump<'a>
+    {
+        (self.start, self.end) = clip(range, self.bytes.len());
+        self
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the dump marking the bits in <b>range</b>, numbered as by the
+    /// object.  Bits beyond its length are ignored.
+    pub fn with_highlight<R: RangeBounds<usize>>(mut self, range: R) -> HexDump<'a>
+    {
+        let (start, end) = clip(range, self.view.bit_len());
+
+        self.highlight = (start < end).then_some((start, end - 1));
+        self
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the enabled columns, in the order they are shown.
+    fn columns(&self) -> impl Iterator<Item = Column>
+    {
+        [(self.binary, Column::Binary), (self.hex, Column::Hex), (self.ascii, Column::Ascii)]
+            .into_iter()
+            .filter_map(|(shown, column)| shown.then_some(column))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn label(column: Column) -> &'static str
+    {
+        match column
+        {
+            Column::Binary => "Binary",
+            Column::Hex => "Hex",
+            Column::Ascii => "ASCII",
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the width of a column for a full row, wide enough for its
+    /// label when there is a header.
+    fn column_width(&self, column: Column) -> usize
+    {
+        let n = self.bytes_per_row;
+        let width = match column
+        {
+            Column::Binary => 9 * n - 1,
+            Column::Hex => 2 * n + (n - 1) / 2,
+            Column::Ascii => n,
+        };
+
+        if self.header { cmp::max(width, HexDump::label(column).len()) } else { width }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the width of the offsets and the ': ' after them, wide enough
+    /// for the label when there is a header.
+    fn offset_area(&self) -> usize
+    {
+        let width = self.offset_width + 2;
+
+        if self.header { cmp::max(width, "Offset".len() + 2) } else { width }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the number of digits <b>offset</b> is written with.
+    fn offset_digits(&self, mut offset: usize) -> usize
+    {
+        let base = if self.offset_base == OffsetBase::Hex { 16 } else { 10 };
+        let mut digits = 1;
+
+        while offset >= base
+        {
+            offset /= base;
+            digits += 1;
+        }
+
+        cmp::max(digits, self.offset_width)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the spaces between columns.
+    fn gap(&self) -> usize
+    {
+        if self.xxd { 2 } else { 3 }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the index of the byte shown at <b>position</b>, which differs
+    /// only when little-endian words are shown as numbers.
+    fn shown_byte(&self, position: usize) -> usize
+    {
+        match self.view.bit_order()
+        {
+            BitOrder::Lsb0Words(width) if !self.xxd =>
+            {
+                let word = position - position % width;
+                let low = cmp::max(word, self.start);
+                let high = cmp::min(word + width, self.end);
+
+                low + high - 1 - position
+            }
+            _ => position
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Returns the number of the bit held at <b>shift</b> in the given byte.
+    fn bit_number(&self, byte: usize, shift: usize) -> usize
+    {
+        let within = if self.view.lsb_first() { shift } else { 7 - shift };
+
+        8 * self.view.physical_byte(byte) + within
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn is_highlighted(&self, bit: usize) -> bool
+    {
+        matches!(self.highlight, Some((start, stop)) if (start..=stop).contains(&bit))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn byte_highlighted(&self, byte: usize) -> bool
+    {
+        (0..8).any(|shift| self.is_highlighted(self.bit_number(byte, shift)))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn write_header(&self, fmt: &mut fmt::Formatter) -> fmt::Result
+    {
+        let mut width = self.offset_area();
+
+        write!(fmt, "{:<1$}", "Offset", width)?;
+
+        for (n, column) in self.columns().enumerate()
+        {
+            let gap = if n == 0 { 0 } else { self.gap() };
+            let column_width = self.column_width(column);
+
+            write!(fmt, "{:gap$}{:<column_width$}", "", HexDump::label(column))?;
+            width += gap + column_width;
+        }
+
+        writeln!(fmt)?;
+
+        for _ in 0..width
+        {
+            fmt.write_char('=')?;
+        }
+
+        writeln!(fmt)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Writes the <b>count</b> bytes shown from <b>position</b>, or when
+    /// <b>marker</b> is set the line marking their highlighted bits.
+    fn write_row(&self,
+                 fmt: &mut fmt::Formatter,
+                 position: usize,
+                 count: usize,
+                 marker: bool) -> fmt::Result
+    {
+        let mut line = Line{fmt, pending: 0};
+
+        match (marker, self.offset_base)
+        {
+            (true, _) => line.pad(self.offset_digits(position) + 2),
+            (false, OffsetBase::Decimal) =>
+                line.put(format_args!("{:01$}: ", position, self.offset_width))?,
+            (false, OffsetBase::Hex) =>
+                line.put(format_args!("{:01$x}: ", position, self.offset_width))?,
+        }
+
+        line.pad(self.offset_area().saturating_sub(self.offset_digits(position) + 2));
+
+        for (n, column) in self.columns().enumerate()
+        {
+            if n != 0
+            {
+                line.pad(self.gap());
+            }
+
+            let mut width = 0;
+
+            for (k, byte) in (position..position + count).map(|p| self.shown_byte(p)).enumerate()
+            {
+                let value = self.bytes[byte];
+                let highlighted = marker && self.byte_highlighted(byte);
+
+                match column
+                {
+                    Column::Binary =>
+                    {
+                        if k != 0
+                        {
+                            line.pad(1);
+                        }
+
+                        for shift in (0..8).rev()
+                        {
+                            let bit = self.bit_number(byte, shift);
+
+                            if marker && !self.is_highlighted(bit)
+                            {
+                                line.pad(1);
+                            }
+                            else if marker
+                            {
+                                line.put(format_args!("^"))?;
+                            }
+                            else if bit >= self.view.bit_len() && !self.xxd
+                            {
+                                line.put(format_args!("-"))?;
+                            }
+                            else
+                            {
+                                line.put(format_args!("{}", (value >> shift) & 1))?;
+                            }
+                        }
+
+                        width += if k != 0 { 9 } else { 8 };
+                    }
+                    Column::Hex =>
+                    {
+                        if k != 0 && k % 2 == 0
+                        {
+                            line.pad(1);
+                            width += 1;
+                        }
+
+                        if highlighted
+                        {
+                            line.put(format_args!("^^"))?;
+                        }
+                        else if marker
+                        {
+                            line.pad(2);
+                        }
+                        else
+                        {
+                            line.put(format_args!("{:02x}", value))?;
+                        }
+
+                        width += 2;
+                    }
+                    Column::Ascii =>
+                    {
+                        if highlighted
+                        {
+                            line.put(format_args!("^"))?;
+                        }
+                        else if marker
+                        {
+                            line.pad(1);
+                        }
+                        else if value.is_ascii_graphic() || value == b' '
+                        {
+                            line.put(format_args!("{}", value as char))?;
+                        }
+                        else
+                        {
+                            line.put(format_args!("."))?;
+                        }
+
+                        width += 1;
+                    }
+                }
+            }
+
+            line.pad(self.column_width(column) - width);
+        }
+
+        writeln!(line.fmt)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl fmt::Display for HexDump<'_>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result
+    {
+        if self.header
+        {
+            self.write_header(fmt)?;
+        }
+
+        for position in (self.start..self.end).step_by(self.bytes_per_row)
+        {
+            let count = cmp::min(self.bytes_per_row, self.end - position);
+
+            self.write_row(fmt, position, count, false)?;
+
+            if (position..position + count).any(|p| self.byte_highlighted(self.shown_byte(p)))
+            {
+                self.write_row(fmt, position, count, true)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Returns the start and end of <b>range</b>, an unbounded end being <b>len</b>,
+// clipped to 0..<b>len</b>.
+fn clip<R: RangeBounds<usize>>(range: R, len: usize) -> (usize, usize)
+{
+    let start = match range.start_bound()
+    {
+        Bound::Included(&s) => s,
+        Bound::Excluded(&s) => s.saturating_add(1),
+        Bound::Unbounded => 0
+    };
+
+    let end = match range.end_bound()
+    {
+        Bound::Included(&e) => e.saturating_add(1),
+        Bound::Excluded(&e) => e,
+        Bound::Unbounded => len
+    };
+
+    let end = cmp::min(end, len);
+
+    (cmp::min(start, end), end)
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Writes a line, holding back the spaces that pad it until something follows
+// them so no line ends in padding.
+struct Line<'a, 'b>
+{
+    fmt: &'a mut fmt::Formatter<'b>,
+    pending: usize
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl Line<'_, '_>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn pad(&mut self, spaces: usize)
+    {
+        self.pending += spaces;
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn put(&mut self, args: fmt::Arguments) -> fmt::Result
+    {
+        for _ in 0..self.pending
+        {
+            self.fmt.write_char(' ')?;
+        }
+
+        self.pending = 0;
+        self.fmt.write_fmt(args)
+    }
+}
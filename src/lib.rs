@@ -1,3 +1,14 @@
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 pub mod bitfield;
+pub mod bitslice;
+pub mod hexdump;
+#[cfg(feature = "alloc")]
+mod parse;
+pub mod storage;
 //pub mod structure;
@@ -0,0 +1,468 @@
+use alloc::vec::Vec;
+use core::cmp;
+use core::str::FromStr;
+
+use crate::bitfield::{BitField, BitFieldError, BitOrder, OverflowPolicy};
+
+///////////////////////////////////////////////////////////////////////////////
+impl BitField
+{
+    ///////////////////////////////////////////////////////////////////////////
+    /// Parses hexadecimal digits into an MSB0 object of 4 bits per digit,
+    /// the first digit at bit 0, so "a5c" gives 12 bits.
+    ///
+    /// <p>An optional <b>0x</b> prefix, and <b>_</b> and whitespace between
+    /// the digits, are allowed.</p>
+    pub fn from_hex_str(text: &str) -> Result<BitField, BitFieldError>
+    {
+        parse_digits(text, "0x", 4)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Parses binary digits into an MSB0 object of 1 bit per digit, so
+    /// "10110" gives 5 bits.  An optional <b>0b</b> prefix, and <b>_</b> and
+    /// whitespace between the digits, are allowed.
+    pub fn from_binary_str(text: &str) -> Result<BitField, BitFieldError>
+    {
+        parse_digits(text, "0b", 1)
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    /// Parses a dump, as written by <b>HexDump</b>, <b>debug_string</b> or
+    /// <b>xxd</b>, of an object with the given bit <b>order</b>.
+    ///
+    /// <p>Each row is an offset, a ':' and columns separated by at least two
+    /// spaces.  Only the first column is read, as binary when the header
+    /// names it so or, without a header, when every row holds groups of 8
+    /// bits not followed by the 4 characters per group of an <b>xxd -g4</b>
+    /// ASCII column, otherwise as hex.  Headers, lines of '=' and lines of
+    /// '^' marking highlighted bits are skipped.</p>
+    ///
+    /// <p>Padding bits shown as '-' give the length, so a binary dump of
+    /// any order reproduces the object, and must be exactly the bits
+    /// beyond it.  A hex dump gives whole bytes.  With
+    /// <b>BitOrder::Lsb0Words</b> the words are taken to be shown most
+    /// significant byte first, as <b>HexDump</b> shows them outside its
+    /// <b>xxd</b> mode.  The offsets are not checked, so a dump of part of
+    /// an object gives just the bytes dumped, starting on a word
+    /// boundary.</p>
+    pub fn from_dump_str(text: &str, order: BitOrder) -> Result<BitField, BitFieldError>
+    {
+        // the first column of each row, where it starts and whether it
+        // could be binary
+        let mut rows = Vec::new();
+        let mut header = None;
+        let mut line_start = 0;
+
+        for line in text.split_inclusive('\n')
+        {
+            let start = line_start;
+            line_start += line.len();
+
+            let raw = line.trim_end_matches(['\n', '\r']);
+            let line = line.trim_end();
+
+            if let Some(labels) = line.trim_start().strip_prefix("Offset")
+            {
+                header = Some(labels.trim_start().starts_with("Binary"));
+                continue;
+            }
+
+            if line.chars().all(|c| c == '=') ||
+               line.chars().all(|c| c == ' ' || c == '^')
+            {
+                continue;
+            }
+
+            // the offset and the ':' after it
+            let offset = line.trim_start();
+            let offset_start = start + line.len() - offset.len();
+            let digits = offset.find(|c: char| !c.is_ascii_hexdigit()).unwrap_or(offset.len());
+
+            if digits == 0 || !offset[digits..].starts_with(':')
+            {
+                return Err(malformed(text, offset_start + digits));
+            }
+
+            // the first column, which ends at two spaces
+            let rest = &offset[digits + 1..];
+            let column = rest.trim_start_matches(' ');
+            let column_start = offset_start + digits + 1 + rest.len() - column.len();
+            let column = &column[..column.find("  ").unwrap_or(column.len())];
+
+            if column.is_empty()
+            {
+                return Err(malformed(text, column_start));
+            }
+
+            // the untrimmed rest of the line, as an ASCII column may end in
+            // spaces
+            let after = raw[column_start - start + column.len()..].trim_start_matches(' ');
+            let groups = column.split(' ').count();
+            let binary = column.split(' ')
+                               .all(|t| t.len() == 8 &&
+                                        t.bytes().all(|b| matches!(b, b'0' | b'1' | b'-'))) &&
+                         after.chars().count() != 4 * groups;
+
+            rows.push((column, column_start, binary));
+        }
+
+        let binary = header.unwrap_or(rows.iter().all(|&(_, _, binary)| binary));
+        let mut bytes = Vec::new();
+
+        // the byte, shift and position in the text of each padding bit
+        let mut padding = Vec::new();
+
+        for (column, column_start, _) in rows
+        {
+            if binary
+            {
+                let mut group_start = column_start;
+
+                for group in column.split(' ')
+                {
+                    if group.len() != 8
+                    {
+                        let end = group.char_indices().nth(8).map_or(group.len(), |(i, _)| i);
+
+                        return Err(malformed(text, group_start + end));
+                    }
+
+                    let mut byte = 0;
+
+                    for (i, c) in group.char_indices()
+                    {
+                        match c
+                        {
+                            '0' | '1' => byte = byte << 1 | (c == '1') as u8,
+                            '-' =>
+                            {
+                                byte <<= 1;
+                                padding.push((bytes.len(), 7 - i, group_start + i));
+                            }
+                            _ => return Err(malformed(text, group_start + i))
+                        }
+                    }
+
+                    bytes.push(byte);
+                    group_start += group.len() + 1;
+                }
+            }
+            else
+            {
+                let mut high_digit = None;
+
+                for (i, c) in column.char_indices()
+                {
+                    let position = column_start + i;
+
+                    match (c, high_digit.take())
+                    {
+                        (' ', None) => {}
+                        (' ', Some(_)) => return Err(malformed(text, position)),
+                        (_, high) =>
+                        {
+                            let digit = c.to_digit(16).ok_or_else(|| malformed(text, position))?;
+
+                            match high
+                            {
+                                None => high_digit = Some(digit),
+                                Some(high) => bytes.push((high << 4 | digit) as u8)
+                            }
+                        }
+                    }
+                }
+
+                if high_digit.is_some()
+                {
+                    return Err(malformed(text, column_start + column.len()));
+                }
+            }
+        }
+
+        // back to storage order from words shown as numbers
+        if let BitOrder::Lsb0Words(width @ 1..) = order
+        {
+            let len = bytes.len();
+
+            for (byte, _, _) in &mut padding
+            {
+                let word = *byte - *byte % width;
+
+                *byte = word + cmp::min(word + width, len) - 1 - *byte;
+            }
+
+            for word in bytes.chunks_mut(width)
+            {
+                word.reverse();
+            }
+        }
+
+        let bits = bytes.len() * 8 - padding.len();
+        let mut result = BitField::from_slice(&bytes).with_bit_order(order);
+        let view = result.as_bitslice();
+
+        for (byte, shift, position) in padding
+        {
+            let within = if view.lsb_first() { shift } else { 7 - shift };
+
+            if 8 * view.physical_byte(byte) + within < bits
+            {
+                return Err(malformed(text, position));
+            }
+        }
+
+        result.truncate_bits(bits);
+        Ok(result)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// Parses any of the text forms the object can be written in:
+///
+/// <ul>
+/// <li>the <b>Debug</b> output, which holds the length and bit order so
+/// reproduces the object exactly;</li>
+/// <li>binary digits with a <b>0b</b> prefix, see
+/// <b>from_binary_str</b>;</li>
+/// <li>dumps, recognised by the ':' after each offset, read as those of
+/// an MSB0 object, see <b>from_dump_str</b>;</li>
+/// <li>otherwise hex digits, see <b>from_hex_str</b>, so the
+/// <b>Display</b> output of an MSB0 object whose length is a multiple of
+/// 4 is read back as the same object.  Digits starting "0b" are read as
+/// binary, so should be given a <b>0x</b> prefix.</li>
+/// </ul>
+impl FromStr for BitField
+{
+    type Err = BitFieldError;
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn from_str(text: &str) -> Result<BitField, BitFieldError>
+    {
+        let trimmed = text.trim_start();
+
+        if trimmed.starts_with("BitField")
+        {
+            parse_debug(text)
+        }
+        else if has_prefix(trimmed, "0b")
+        {
+            BitField::from_binary_str(text)
+        }
+        else if text.contains(':') || trimmed.starts_with("Offset")
+        {
+            BitField::from_dump_str(text, BitOrder::Msb0)
+        }
+        else
+        {
+            BitField::from_hex_str(text)
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Returns the error for the character at <b>index</b> of <b>text</b>.
+fn malformed(text: &str, index: usize) -> BitFieldError
+{
+    let before = &text[..index];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+    BitFieldError::Malformed{line: before.matches('\n').count() + 1,
+                             column: before[line_start..].chars().count() + 1}
+}
+
+///////////////////////////////////////////////////////////////////////////////
+fn has_prefix(text: &str, prefix: &str) -> bool
+{
+    text.get(..prefix.len()).is_some_and(|p| p.eq_ignore_ascii_case(prefix))
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Parses digits of <b>digit_bits</b> bits each, after an optional
+// <b>prefix</b>.
+fn parse_digits(text: &str, prefix: &str, digit_bits: usize) -> Result<BitField, BitFieldError>
+{
+    let trimmed = text.trim_start();
+    let mut start = text.len() - trimmed.len();
+
+    if has_prefix(trimmed, prefix)
+    {
+        start += prefix.len();
+    }
+
+    let mut result = BitField::new();
+
+    for (i, c) in text[start..].char_indices()
+    {
+        if c == '_' || c.is_whitespace()
+        {
+            continue;
+        }
+
+        let digit = c.to_digit(1 << digit_bits).ok_or_else(|| malformed(text, start + i))?;
+
+        result.push_bits(digit as u64, digit_bits)?;
+    }
+
+    Ok(result)
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Parses the <b>Debug</b> output, in either its compact or pretty form.
+fn parse_debug(text: &str) -> Result<BitField, BitFieldError>
+{
+    let mut cursor = Cursor{text, position: 0};
+
+    cursor.expect("BitField")?;
+    cursor.expect("{")?;
+    cursor.expect("bits")?;
+    cursor.expect(":")?;
+
+    let bits = cursor.number()?;
+
+    cursor.expect(",")?;
+    cursor.expect("order")?;
+    cursor.expect(":")?;
+
+    let order = if cursor.accept("Lsb0Words")
+    {
+        cursor.expect("(")?;
+
+        let width_start = cursor.skip_whitespace();
+        let width = cursor.number()?;
+
+        if width == 0
+        {
+            return Err(malformed(text, width_start));
+        }
+
+        cursor.accept(",");
+        cursor.expect(")")?;
+        BitOrder::Lsb0Words(width)
+    }
+    else if cursor.accept("Msb0")
+    {
+        BitOrder::Msb0
+    }
+    else if cursor.accept("Lsb0")
+    {
+        BitOrder::Lsb0
+    }
+    else
+    {
+        return Err(malformed(text, cursor.position));
+    };
+
+    cursor.expect(",")?;
+    cursor.expect("value")?;
+    cursor.expect(":")?;
+    cursor.expect("0x")?;
+
+    let digits_start = cursor.position;
+    let digits = cursor.take_while(|c| c.is_ascii_hexdigit());
+
+    cursor.accept(",");
+    cursor.expect("}")?;
+    cursor.finish()?;
+
+    // checked before the object is made, so the length can be trusted
+    let expected = bits.div_ceil(4);
+
+    if digits.len() != expected
+    {
+        return Err(malformed(text, digits_start + digits.len().min(expected)));
+    }
+
+    let mut result = BitField::with_bit_capacity(bits).with_bit_order(order);
+
+    for (i, c) in digits.char_indices()
+    {
+        let (start_bit, stop_bit) = result.as_bitslice().digit_range(expected - 1 - i, 4);
+        let digit = c.to_digit(16).unwrap_or(0) as u64;
+
+        // only the most significant digit can be short, and too big
+        result.insert_u64_with(digit, start_bit, stop_bit, OverflowPolicy::Checked)
+              .map_err(|_| malformed(text, digits_start + i))?;
+    }
+
+    Ok(result)
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Reads the tokens of the <b>Debug</b> output, skipping whitespace between
+// them.
+struct Cursor<'a>
+{
+    text: &'a str,
+    position: usize
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl<'a> Cursor<'a>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    // Skips any whitespace, returning the position after it.
+    fn skip_whitespace(&mut self) -> usize
+    {
+        let rest = &self.text[self.position..];
+
+        self.position += rest.len() - rest.trim_start().len();
+        self.position
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    // Consumes <b>token</b> if it is next, returning whether it was.
+    fn accept(&mut self, token: &str) -> bool
+    {
+        self.skip_whitespace();
+
+        let found = self.text[self.position..].starts_with(token);
+
+        if found
+        {
+            self.position += token.len();
+        }
+
+        found
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn expect(&mut self, token: &str) -> Result<(), BitFieldError>
+    {
+        if self.accept(token) { Ok(()) } else { Err(malformed(self.text, self.position)) }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn take_while<F: Fn(char) -> bool>(&mut self, f: F) -> &'a str
+    {
+        let rest = &self.text[self.position..];
+        let len = rest.find(|c| !f(c)).unwrap_or(rest.len());
+
+        self.position += len;
+        &rest[..len]
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn number(&mut self) -> Result<usize, BitFieldError>
+    {
+        let start = self.skip_whitespace();
+
+        self.take_while(|c| c.is_ascii_digit())
+            .parse()
+            .map_err(|_| malformed(self.text, start))
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    // Checks nothing but whitespace is left.
+    fn finish(&mut self) -> Result<(), BitFieldError>
+    {
+        if self.skip_whitespace() == self.text.len()
+        {
+            Ok(())
+        }
+        else
+        {
+            Err(malformed(self.text, self.position))
+        }
+    }
+}
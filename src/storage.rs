@@ -0,0 +1,220 @@
+use core::{mem, slice};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+///////////////////////////////////////////////////////////////////////////////
+/// The bytes a BitField stores its bits in.
+///
+/// <p>A BitField only ever reads and writes its bits through
+/// <b>as_bytes</b> and <b>as_bytes_mut</b>, using the first
+/// <b>len()</b> bytes, so every storage behaves identically.  Storage may
+/// hold more bytes than are in use, the unused bytes are ignored.</p>
+pub trait BitStorage
+{
+    /// Returns every byte of the storage.
+    fn as_bytes(&self) -> &[u8];
+
+    /// Returns every byte of the storage for writing.
+    fn as_bytes_mut(&mut self) -> &mut [u8];
+
+    /// Makes at least <b>len</b> bytes available, keeping the bytes before
+    /// <b>len</b> in place.  Returns false if the storage can not hold that
+    /// many.
+    fn set_byte_len(&mut self, len: usize) -> bool;
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[cfg(feature = "alloc")]
+impl BitStorage for Vec<u8>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn as_bytes(&self) -> &[u8]
+    {
+        self
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn as_bytes_mut(&mut self) -> &mut [u8]
+    {
+        self
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn set_byte_len(&mut self, len: usize) -> bool
+    {
+        self.resize(len, 0);
+        true
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl<const N: usize> BitStorage for [u8; N]
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn as_bytes(&self) -> &[u8]
+    {
+        self
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn as_bytes_mut(&mut self) -> &mut [u8]
+    {
+        self
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn set_byte_len(&mut self, len: usize) -> bool
+    {
+        len <= N
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+impl BitStorage for &mut [u8]
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn as_bytes(&self) -> &[u8]
+    {
+        self
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn as_bytes_mut(&mut self) -> &mut [u8]
+    {
+        self
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn set_byte_len(&mut self, len: usize) -> bool
+    {
+        len <= self.len()
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Arrays of words are used as the bytes they occupy in memory, so each word
+// holds its bytes in native byte order.  On a little-endian machine that makes
+// BitOrder::Lsb0Words of the word size number the bits of each word from its
+// least significant bit.
+macro_rules! word_array_storage
+{
+    ($($t:ty),*) =>
+    {
+        $(
+            impl<const N: usize> BitStorage for [$t; N]
+            {
+                fn as_bytes(&self) -> &[u8]
+                {
+                    // SAFETY: u8 has no alignment requirement and the words
+                    // have no padding, so their memory is N * size bytes.
+                    unsafe
+                    {
+                        slice::from_raw_parts(self.as_ptr().cast::<u8>(),
+                                              mem::size_of_val(self))
+                    }
+                }
+
+                fn as_bytes_mut(&mut self) -> &mut [u8]
+                {
+                    // SAFETY: as above, and every byte pattern is a valid
+                    // word.
+                    unsafe
+                    {
+                        slice::from_raw_parts_mut(self.as_mut_ptr().cast::<u8>(),
+                                                  mem::size_of_val(self))
+                    }
+                }
+
+                fn set_byte_len(&mut self, len: usize) -> bool
+                {
+                    len <= mem::size_of_val(self)
+                }
+            }
+        )*
+    }
+}
+
+word_array_storage!(u16, u32, u64);
+
+///////////////////////////////////////////////////////////////////////////////
+/// Storage that keeps up to <b>N</b> bytes inline and moves them to the heap
+/// if it needs more, after which it stays there.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InlineBytes<const N: usize>
+{
+    Inline([u8; N]),
+    Heap(Vec<u8>),
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[cfg(feature = "alloc")]
+impl<const N: usize> InlineBytes<N>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn new() -> InlineBytes<N>
+    {
+        InlineBytes::Inline([0; N])
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    pub fn is_inline(&self) -> bool
+    {
+        matches!(self, InlineBytes::Inline(_))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[cfg(feature = "alloc")]
+impl<const N: usize> Default for InlineBytes<N>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn default() -> InlineBytes<N>
+    {
+        InlineBytes::new()
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[cfg(feature = "alloc")]
+impl<const N: usize> BitStorage for InlineBytes<N>
+{
+    ///////////////////////////////////////////////////////////////////////////
+    fn as_bytes(&self) -> &[u8]
+    {
+        match self
+        {
+            InlineBytes::Inline(bytes) => bytes,
+            InlineBytes::Heap(bytes) => bytes
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn as_bytes_mut(&mut self) -> &mut [u8]
+    {
+        match self
+        {
+            InlineBytes::Inline(bytes) => bytes,
+            InlineBytes::Heap(bytes) => bytes
+        }
+    }
+
+    ///////////////////////////////////////////////////////////////////////////
+    fn set_byte_len(&mut self, len: usize) -> bool
+    {
+        match self
+        {
+            InlineBytes::Inline(_) if len <= N => {}
+            InlineBytes::Inline(bytes) =>
+            {
+                let mut heap = bytes.to_vec();
+                heap.resize(len, 0);
+                *self = InlineBytes::Heap(heap);
+            }
+            InlineBytes::Heap(bytes) => bytes.resize(len, 0)
+        }
+
+        true
+    }
+}
@@ -1,9 +1,15 @@
 ///////////////////////////////////////////////////////////////////////////////
-#![cfg(test)]
-extern crate bits;
-extern crate test;
+// The tests build Vec backed objects, so they run with the default std
+// feature and again with only alloc:
+//     cargo test --no-default-features --features alloc
+// Those that need no allocator are in fixed_storage_tests.rs.
+#![cfg(feature = "alloc")]
 
-use bits::bitfield::BitField;
+use bits::bitfield::{Alignment, BitField, BitOrder, ByteOrder, BitFieldError, FixedPoint, OverflowPolicy,
+                     Rounding, SignedEncoding};
+use bits::hexdump::OffsetBase;
+use bits::storage::{BitStorage, InlineBytes};
+use std::cmp;
 
 
 ///////////////////////////////////////////////////////////////////////////////
@@ -28,7 +34,7 @@ fn bitfield_insert_retrieve()
 
 ///////////////////////////////////////////////////////////////////////////////
 #[test]
-#[should_fail]
+#[should_panic]
 fn bitfield_insert_invalid_index()
 {
     let mut bf = BitField::with_capacity(4);
@@ -54,7 +60,7 @@ fn bitfield_bitwise_and()
     // 000000: 11001100 11001100 11001100 11001100   cccc cccc
 
     // Verify transitive
-    assert!((bf1 & bf2) == (bf2 & bf1));
+    assert!((&bf1 & &bf2) == (&bf2 & &bf1));
 
     let result = bf1 & bf2;
     println!("{}", result.debug_string());
@@ -82,7 +88,7 @@ fn bitfield_bitwise_and_different_sizes()
     // 000000: 11001100 11001100 11001100 11001100   cccc cccc
 
     // Verify transitive
-    assert!((bf1 & bf2) == (bf2 & bf1));
+    assert!((&bf1 & &bf2) == (&bf2 & &bf1));
 
     let result = bf1 & bf2;
     println!("{}", result.debug_string());
@@ -110,7 +116,7 @@ fn bitfield_bitwise_or()
     // 000000: 11001100 11001100 11001100 11001100   cccc cccc
 
     // Verify transitive
-    assert!((bf1 | bf2) == (bf2 | bf1));
+    assert!((&bf1 | &bf2) == (&bf2 | &bf1));
 
     let result = bf1 | bf2;
     println!("{}", result.debug_string());
@@ -138,7 +144,7 @@ fn bitfield_bitwise_or_different_sizes()
     // 000000: 11001100 11001100 11001100 11001100   cccc cccc
 
     // Verify transitive
-    assert!((bf1 | bf2) == (bf2 | bf1));
+    assert!((&bf1 | &bf2) == (&bf2 | &bf1));
 
     let result = bf1 | bf2;
     println!("{}", result.debug_string());
@@ -166,7 +172,7 @@ fn bitfield_bitwise_xor()
     // 000000: 11001100 11001100 11001100 11001100   cccc cccc
 
     // Verify transitive
-    assert!((bf1 ^ bf2) == (bf2 ^ bf1));
+    assert!((&bf1 ^ &bf2) == (&bf2 ^ &bf1));
 
     let result = bf1 ^ bf2;
     println!("{}", result.debug_string());
@@ -194,7 +200,7 @@ fn bitfield_bitwise_xor_different_sizes()
     // 000000: 11001100 11001100 11001100 11001100   cccc cccc
 
     // Verify transitive
-    assert!((bf1 ^ bf2) == (bf2 ^ bf1));
+    assert!((&bf1 ^ &bf2) == (&bf2 ^ &bf1));
 
     let result = bf1 ^ bf2;
     println!("{}", result.debug_string());
@@ -265,9 +271,9 @@ fn bitfield_insert_retrieve_stress()
 {
     let mut bitfield = BitField::with_capacity(10);
 
-    for i in range(0, 8)
+    for i in 0..8
     {
-        for j in range(0, 65536)
+        for j in 0..65536u64
         {
             //println!("{}", bitfield.debug_string());
             assert!(bitfield.insert_u64(j, i, i + 15).is_ok());
@@ -276,8 +282,1606 @@ fn bitfield_insert_retrieve_stress()
                 Ok(value) if value == j => (),
                 Ok(value) =>
                     println!("FAULT\ni = {}\nj = {}\nvalue = {}", i, j, value),
-                Err(e) => println!("Error: {}", e)
+                Err(e) => println!("Error: {:?}", e)
             }
         }
     }
 }
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_display()
+{
+    let bf = BitField::from_slice(&[0xa5, 0x5a, 0x01]);
+
+    assert_eq!(format!("{}", bf), "a55a01");
+    assert_eq!(format!("{:+}", bf), "a5_5a01");
+    assert_eq!(format!("{:?}", bf), "BitField { bits: 24, order: Msb0, value: 0xa55a01 }");
+    assert_eq!(format!("{}", BitField::new()), "");
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_format_radix()
+{
+    let mut bf = BitField::from_slice(&[0xa5, 0x5a, 0xc0]);
+    bf.truncate_bits(18);
+
+    // the digits are exactly the 18 bits, read as retrieve_u64 reads them
+    assert_eq!(format!("{:b}", bf), "101001010101101011");
+    assert_eq!(format!("{:x}", bf), format!("{:x}", bf.retrieve_u64(0, 17).unwrap()));
+    assert_eq!(format!("{:x}", bf), "2956b");
+    assert_eq!(format!("{:X}", bf), "2956B");
+    assert_eq!(format!("{:o}", bf), "512553");
+
+    // flags
+    assert_eq!(format!("{:#x}", bf), "0x2956b");
+    assert_eq!(format!("{:#b}", bf), "0b101001010101101011");
+    assert_eq!(format!("{:#o}", bf), "0o512553");
+    assert_eq!(format!("{:+b}", bf), "10_10010101_01101011");
+    assert_eq!(format!("{:+#X}", bf), "0x2_956B");
+    assert_eq!(format!("{:>8x}|", bf), "   2956b|");
+    assert_eq!(format!("{:*<8x}|", bf), "2956b***|");
+    assert_eq!(format!("{:^9x}|", bf), "  2956b  |");
+    assert_eq!(format!("{:#010x}", bf), "0x0002956b");
+    assert_eq!(format!("{:#?}", bf),
+               "BitField {\n    bits: 18,\n    order: Msb0,\n    value: 0x2956b,\n}");
+
+    // lsb first orders read the highest numbered bit first
+    let mut bf = BitField::with_bit_capacity(6).with_bit_order(BitOrder::Lsb0);
+    bf.set_bit(0).unwrap();
+    bf.set_bit(5).unwrap();
+    assert_eq!(format!("{:b}", bf), "100001");
+    assert_eq!(format!("{:x} {:o}", bf, bf), "21 41");
+
+    // empty objects have no digits
+    let bf = BitField::new();
+    assert_eq!(format!("{:b}{:x}{:o}{:+X}", bf, bf, bf, bf), "");
+    assert_eq!(format!("{:#x}", bf), "0x");
+    assert_eq!(format!("{:3b}|", bf), "   |");
+
+    // views format the same way
+    let bf = BitField::from_slice(&[0xa5, 0x5a]);
+    assert_eq!(format!("{:b}", bf.slice(4..9).unwrap()), "01010");
+    assert_eq!(format!("{:#x}", bf.slice(4..12).unwrap()), "0x55");
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_operators_by_value_and_reference()
+{
+    let bf1 = BitField::from_slice(&[0xa5, 0xa5]);
+    let bf2 = BitField::from_slice(&[0xcc, 0xcc]);
+
+    assert!((&bf1 & &bf2) == (bf1.clone() & bf2.clone()));
+    assert!((&bf1 | bf2.clone()) == (bf1.clone() | &bf2));
+    assert!((!&bf1).as_slice() == [0x5a, 0x5a]);
+    assert!((bf1 >> 16).as_slice() == [0x00, 0x00]);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_insert_retrieve_bytes()
+{
+    let mut bf = BitField::with_capacity(4);
+
+    assert!(bf.insert_bytes(&[0x12, 0x34], 0, 11).is_ok());
+    // Offset  Binary                                Hex
+    // =======================================================
+    // 000000: 00100011 01000000 00000000 00000000   2340 0000
+    assert!(bf.retrieve_u64(0, 31).unwrap() == 0x23400000);
+    assert!(bf.retrieve_bytes(0, 11).unwrap() == [0x02, 0x34]);
+
+    assert!(bf.insert_bytes(&[0xa5, 0xa5, 0xa5], 5, 28).is_ok());
+    // Offset  Binary                                Hex
+    // =======================================================
+    // 000000: 00100101 00101101 00101101 00101000   252d 2d28
+    assert!(bf.retrieve_u64(0, 31).unwrap() == 0x252d2d28);
+    assert!(bf.retrieve_bytes(5, 28).unwrap() == [0xa5, 0xa5, 0xa5]);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_insert_retrieve_bytes_wide()
+{
+    // a 200 bit key at an unaligned offset
+    let key: Vec<u8> = (0..25).map(|i| (i * 37 + 11) as u8).collect();
+    let mut bf = BitField::with_capacity(27);
+
+    assert!(bf.insert_bytes(&key, 3, 202).is_ok());
+    assert!(bf.retrieve_u64(0, 2).unwrap() == 0);
+    assert!(bf.retrieve_u64(203, 215).unwrap() == 0);
+    assert!(bf.retrieve_bytes(3, 202).unwrap() == key);
+
+    // an unaligned 13 byte payload
+    let payload = [0xffu8; 13];
+    let mut bf = BitField::with_capacity(27);
+    assert!(bf.insert_bytes(&payload, 100, 203).is_ok());
+    assert!(bf.retrieve_bytes(100, 203).unwrap() == payload);
+    assert!(bf.retrieve_u64(99, 99).unwrap() == 0);
+    assert!(bf.retrieve_u64(204, 204).unwrap() == 0);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_insert_retrieve_bytes_errors()
+{
+    let mut bf = BitField::with_capacity(4);
+
+    assert!(bf.insert_bytes(&[0xff], 8, 7) ==
+            Err(BitFieldError::NegativeRange{start_bit: 8, stop_bit: 7}));
+    assert!(bf.insert_bytes(&[0xff; 8], 0, 32) ==
+            Err(BitFieldError::InvalidIndex{start_bit: 0, stop_bit: 32,
+                                            bit_len: 32}));
+    assert!(bf.insert_bytes(&[0xff], 0, 8) ==
+            Err(BitFieldError::ExceededDataRange{width: 9, max_width: 8}));
+    assert!(bf.retrieve_bytes(8, 7) ==
+            Err(BitFieldError::NegativeRange{start_bit: 8, stop_bit: 7}));
+    assert!(bf.retrieve_bytes(0, 32) ==
+            Err(BitFieldError::InvalidIndex{start_bit: 0, stop_bit: 32,
+                                            bit_len: 32}));
+    assert!(bf.retrieve_u64(0, 31).unwrap() == 0);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_insert_retrieve_signed()
+{
+    let mut bf = BitField::with_capacity(4);
+
+    // a 12 bit sensor reading
+    assert!(bf.insert_i16(-5, 4, 15).is_ok());
+    // Offset  Binary                                Hex
+    // =======================================================
+    // 000000: 00001111 11111011 00000000 00000000   0ffb 0000
+    assert!(bf.retrieve_u64(0, 31).unwrap() == 0x0ffb0000);
+    assert!(bf.retrieve_i16(4, 15).unwrap() == -5);
+    assert!(bf.retrieve_i64(4, 15).unwrap() == -5);
+    assert!(bf.retrieve_u16(4, 15).unwrap() == 0xffb);
+
+    assert!(bf.insert_i8(-128, 16, 23).is_ok());
+    assert!(bf.retrieve_i8(16, 23).unwrap() == -128);
+    assert!(bf.insert_i32(2047, 20, 31).is_ok());
+    assert!(bf.retrieve_i32(20, 31).unwrap() == 2047);
+    assert!(bf.insert_i64(i64::MIN, 0, 63) ==
+            Err(BitFieldError::InvalidIndex{start_bit: 0, stop_bit: 63,
+                                            bit_len: 32}));
+
+    let mut bf = BitField::with_capacity(8);
+    assert!(bf.insert_i64(i64::MIN, 0, 63).is_ok());
+    assert!(bf.retrieve_i64(0, 63).unwrap() == i64::MIN);
+    assert!(bf.insert_i64(-1, 0, 0).is_ok());
+    assert!(bf.retrieve_i64(0, 0).unwrap() == -1);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_insert_signed_overflow()
+{
+    let mut bf = BitField::with_capacity(4);
+
+    assert!(bf.insert_i16(2047, 4, 15).is_ok());
+    assert!(bf.insert_i16(-2048, 4, 15).is_ok());
+    assert!(bf.insert_i16(2048, 4, 15) ==
+            Err(BitFieldError::Overflow{width: 12}));
+    assert!(bf.insert_i16(-2049, 4, 15) ==
+            Err(BitFieldError::Overflow{width: 12}));
+    assert!(bf.insert_i64(1, 0, 0) == Err(BitFieldError::Overflow{width: 1}));
+    assert!(bf.insert_i8(1, 0, 8) ==
+            Err(BitFieldError::ExceededDataRange{width: 9, max_width: 8}));
+    assert!(bf.retrieve_i8(0, 8) ==
+            Err(BitFieldError::ExceededDataRange{width: 9, max_width: 8}));
+    assert!(bf.retrieve_i16(4, 15).unwrap() == -2048);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_insert_retrieve_ones_complement_sign_magnitude()
+{
+    let mut bf = BitField::with_capacity(2);
+
+    assert!(bf.insert_signed(-5, 0, 7, SignedEncoding::OnesComplement).is_ok());
+    assert!(bf.retrieve_u64(0, 7).unwrap() == 0xfa);
+    assert!(bf.retrieve_signed(0, 7, SignedEncoding::OnesComplement).unwrap() == -5);
+
+    assert!(bf.insert_signed(-5, 8, 15, SignedEncoding::SignMagnitude).is_ok());
+    assert!(bf.retrieve_u64(8, 15).unwrap() == 0x85);
+    assert!(bf.retrieve_signed(8, 15, SignedEncoding::SignMagnitude).unwrap() == -5);
+
+    // negative zero
+    assert!(bf.retrieve_signed(0, 7, SignedEncoding::TwosComplement).unwrap() == -6);
+    assert!(bf.insert_u64(0xff, 0, 7).is_ok());
+    assert!(bf.retrieve_signed(0, 7, SignedEncoding::OnesComplement).unwrap() == 0);
+    assert!(bf.insert_u64(0x80, 8, 15).is_ok());
+    assert!(bf.retrieve_signed(8, 15, SignedEncoding::SignMagnitude).unwrap() == 0);
+
+    // symmetric ranges
+    assert!(bf.insert_signed(-127, 0, 7, SignedEncoding::OnesComplement).is_ok());
+    assert!(bf.insert_signed(-128, 0, 7, SignedEncoding::OnesComplement) ==
+            Err(BitFieldError::Overflow{width: 8}));
+    assert!(bf.insert_signed(-128, 8, 15, SignedEncoding::SignMagnitude) ==
+            Err(BitFieldError::Overflow{width: 8}));
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_insert_retrieve_float()
+{
+    let mut bf = BitField::with_capacity(13);
+
+    assert!(bf.insert_f32(1.5, 3).is_ok());
+    assert!(bf.retrieve_u64(3, 34).unwrap() == 0x3fc00000);
+    assert!(bf.retrieve_f32(3).unwrap() == 1.5);
+
+    assert!(bf.insert_f64(-2.25, 37).is_ok());
+    assert!(bf.retrieve_u64(37, 100).unwrap() == 0xc002000000000000);
+    assert!(bf.retrieve_f64(37).unwrap() == -2.25);
+    assert!(bf.retrieve_f32(3).unwrap() == 1.5);
+
+    assert!(bf.insert_f64(1.0, 41) ==
+            Err(BitFieldError::InvalidIndex{start_bit: 41, stop_bit: 104,
+                                            bit_len: 104}));
+    assert!(bf.retrieve_f32(usize::MAX) ==
+            Err(BitFieldError::InvalidIndex{start_bit: usize::MAX,
+                                            stop_bit: usize::MAX,
+                                            bit_len: 104}));
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_insert_retrieve_fixed()
+{
+    let mut bf = BitField::with_capacity(4);
+    let q3_12 = FixedPoint::q(12);
+
+    assert!(bf.insert_fixed(1.5, 0, 15, &q3_12).is_ok());
+    assert!(bf.retrieve_u64(0, 15).unwrap() == 0x1800);
+    assert!(bf.retrieve_fixed(0, 15, &q3_12).unwrap() == 1.5);
+
+    assert!(bf.insert_fixed(-1.5, 0, 15, &q3_12).is_ok());
+    assert!(bf.retrieve_u64(0, 15).unwrap() == 0xe800);
+    assert!(bf.retrieve_fixed(0, 15, &q3_12).unwrap() == -1.5);
+
+    // engineering units: 0.5 degree LSB starting at -40 degrees
+    let temperature = FixedPoint::new(0.5, -40.0, false);
+    assert!(bf.insert_fixed(25.0, 20, 27, &temperature).is_ok());
+    assert!(bf.retrieve_u64(20, 27).unwrap() == 130);
+    assert!(bf.retrieve_fixed(20, 27, &temperature).unwrap() == 25.0);
+    assert!(bf.retrieve_fixed(0, 15, &q3_12).unwrap() == -1.5);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_insert_fixed_rounding_saturation()
+{
+    let mut bf = BitField::with_capacity(2);
+    let q = FixedPoint::q(1);
+
+    // 1.25 is halfway between 1.0 and 1.5
+    let cases = [(Rounding::Nearest, 1.25, 3, -1.25, -3),
+                 (Rounding::NearestEven, 1.25, 2, -1.25, -2),
+                 (Rounding::Floor, 1.25, 2, -1.25, -3),
+                 (Rounding::Ceil, 1.25, 3, -1.25, -2),
+                 (Rounding::TowardZero, 1.25, 2, -1.25, -2)];
+
+    for &(rounding, pos, pos_raw, neg, neg_raw) in cases.iter()
+    {
+        let format = q.with_rounding(rounding);
+        assert!(bf.insert_fixed(pos, 0, 7, &format).is_ok());
+        assert!(bf.retrieve_i64(0, 7).unwrap() == pos_raw);
+        assert!(bf.insert_fixed(neg, 0, 7, &format).is_ok());
+        assert!(bf.retrieve_i64(0, 7).unwrap() == neg_raw);
+    }
+
+    assert!(bf.insert_fixed(8.0, 0, 15, &FixedPoint::q(12)).is_ok());
+    assert!(bf.retrieve_u64(0, 15).unwrap() == 0x7fff);
+    assert!(bf.insert_fixed(-100.0, 0, 15, &FixedPoint::q(12)).is_ok());
+    assert!(bf.retrieve_u64(0, 15).unwrap() == 0x8000);
+
+    let unsigned = FixedPoint::new(1.0, 0.0, false);
+    assert!(bf.insert_fixed(-3.0, 0, 7, &unsigned).is_ok());
+    assert!(bf.retrieve_u64(0, 7).unwrap() == 0);
+    assert!(bf.insert_fixed(f64::INFINITY, 0, 7, &unsigned).is_ok());
+    assert!(bf.retrieve_u64(0, 7).unwrap() == 0xff);
+    assert!(bf.insert_fixed(f64::NAN, 0, 7, &unsigned) ==
+            Err(BitFieldError::Overflow{width: 8}));
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_bit_order_lsb0()
+{
+    let mut bf = BitField::with_capacity(4).with_bit_order(BitOrder::Lsb0);
+
+    assert!(bf.bit_order() == BitOrder::Lsb0);
+    assert!(bf.insert_u64(0xa5, 0, 7).is_ok());
+    assert!(bf.insert_u64(0xf, 6, 9).is_ok());
+    // Offset  Binary                                Hex
+    // =======================================================
+    // 000000: 00000000 00000000 00000011 11100101   0000 03e5
+    assert!(bf.as_slice() == [0x00, 0x00, 0x03, 0xe5]);
+    assert!(bf.retrieve_u64(0, 31).unwrap() == 0x3e5);
+    assert!(bf.retrieve_u64(5, 9).unwrap() == 0x1f);
+
+    let mut bf = BitField::with_capacity(2).with_bit_order(BitOrder::Lsb0);
+    assert!(bf.insert_i16(-5, 4, 15).is_ok());
+    assert!(bf.as_slice() == [0xff, 0xb0]);
+    assert!(bf.retrieve_i16(4, 15).unwrap() == -5);
+
+    let mut bf = BitField::from_slice(&[0x01]).with_bit_order(BitOrder::Lsb0);
+    bf.grow(1);
+    assert!(bf.as_slice() == [0x00, 0x01]);
+    assert!(bf.retrieve_u64(0, 7).unwrap() == 0x01);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_bit_order_lsb0_words()
+{
+    let mut bf = BitField::from_slice(&[0x34, 0x12, 0x78, 0x56])
+        .with_bit_order(BitOrder::Lsb0Words(2));
+
+    assert!(bf.retrieve_u64(0, 15).unwrap() == 0x1234);
+    assert!(bf.retrieve_u64(16, 31).unwrap() == 0x5678);
+    assert!(bf.retrieve_u64(4, 11).unwrap() == 0x23);
+    assert!(bf.retrieve_u64(0, 31).unwrap() == 0x56781234);
+
+    assert!(bf.debug_string() ==
+            "Offset  Binary                                Hex      \n\
+             =======================================================\n\
+             000000: 00010010 00110100 01010110 01111000   1234 5678\n");
+
+    assert!(bf.insert_u64(0xabc, 20, 31).is_ok());
+    assert!(bf.as_slice() == [0x34, 0x12, 0xc8, 0xab]);
+    assert!(bf.retrieve_u64(16, 31).unwrap() == 0xabc8);
+
+    let mut bf = BitField::with_capacity(2).with_bit_order(BitOrder::Lsb0Words(1));
+    assert!(bf.insert_bytes(&[0x12, 0x34], 0, 15).is_ok());
+    assert!(bf.as_slice() == [0x34, 0x12]);
+    assert!(bf.retrieve_bytes(0, 15).unwrap() == [0x12, 0x34]);
+    assert!(bf.retrieve_bytes(4, 15).unwrap() == [0x01, 0x23]);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_bit_order_shifts()
+{
+    // shifts move bits towards (<<) or away from (>>) the most significant
+    // bit whatever the numbering
+    let bf = BitField::from_slice(&[0x00, 0x00, 0x00, 0x81]).with_bit_order(BitOrder::Lsb0);
+    assert!((&bf << 4).as_slice() == [0x00, 0x00, 0x08, 0x10]);
+    assert!((&bf >> 1).as_slice() == [0x00, 0x00, 0x00, 0x40]);
+    assert!((&bf << 4).bit_order() == BitOrder::Lsb0);
+
+    let bf = BitField::from_slice(&[0x81, 0x00, 0x00, 0x00])
+        .with_bit_order(BitOrder::Lsb0Words(4));
+    assert!((&bf << 4).as_slice() == [0x10, 0x08, 0x00, 0x00]);
+    assert!((&bf >> 1).as_slice() == [0x40, 0x00, 0x00, 0x00]);
+    assert!((bf << 28 >> 28).as_slice() == [0x01, 0x00, 0x00, 0x00]);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+#[should_panic]
+fn bitfield_bit_order_zero_width_words()
+{
+    BitField::new().with_bit_order(BitOrder::Lsb0Words(0));
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_insert_retrieve_endian()
+{
+    let vectors = [(ByteOrder::BigEndian, [0x0a, 0x0b, 0x0c, 0x0d]),
+                   (ByteOrder::LittleEndian, [0x0d, 0x0c, 0x0b, 0x0a]),
+                   (ByteOrder::Pdp, [0x0b, 0x0a, 0x0d, 0x0c])];
+
+    for &(byte_order, bytes) in vectors.iter()
+    {
+        let mut bf = BitField::with_capacity(4);
+        assert!(bf.insert_endian(0x0a0b0c0d, 0, 31, byte_order).is_ok());
+        assert!(bf.as_slice() == bytes);
+        assert!(bf.retrieve_endian(0, 31, byte_order).unwrap() == 0x0a0b0c0d);
+
+        let bf = BitField::from_slice(&bytes);
+        assert!(bf.retrieve_endian(0, 31, byte_order).unwrap() == 0x0a0b0c0d);
+    }
+
+    // 24 bits with the odd most significant byte in its own word
+    let mut bf = BitField::with_capacity(3);
+    assert!(bf.insert_endian(0x0a0b0c, 0, 23, ByteOrder::Pdp).is_ok());
+    assert!(bf.as_slice() == [0x0a, 0x0c, 0x0b]);
+    assert!(bf.retrieve_endian(0, 23, ByteOrder::Pdp).unwrap() == 0x0a0b0c);
+
+    let mut bf = BitField::with_capacity(8);
+    assert!(bf.insert_endian(0x0102030405060708, 0, 63, ByteOrder::LittleEndian).is_ok());
+    assert!(bf.as_slice() == [0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01]);
+    assert!(bf.retrieve_endian(0, 63, ByteOrder::Pdp).unwrap() == 0x0708050603040102);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_insert_retrieve_endian_unaligned()
+{
+    let mut bf = BitField::with_capacity(3);
+
+    assert!(bf.insert_endian(0x1234, 4, 19, ByteOrder::LittleEndian).is_ok());
+    // Offset  Binary                                Hex
+    // =======================================================
+    // 000000: 00000011 01000001 00100000            0341 20
+    assert!(bf.as_slice() == [0x03, 0x41, 0x20]);
+    assert!(bf.retrieve_endian(4, 19, ByteOrder::LittleEndian).unwrap() == 0x1234);
+    assert!(bf.retrieve_u64(4, 19).unwrap() == 0x3412);
+
+    // 12 bits, the most significant byte holds the remaining 4 bits
+    let mut bf = BitField::with_capacity(2);
+    assert!(bf.insert_endian(0xabc, 1, 12, ByteOrder::LittleEndian).is_ok());
+    assert!(bf.retrieve_u64(1, 8).unwrap() == 0xbc);
+    assert!(bf.retrieve_u64(9, 12).unwrap() == 0xa);
+    assert!(bf.retrieve_endian(1, 12, ByteOrder::LittleEndian).unwrap() == 0xabc);
+
+    assert!(bf.insert_endian(0xabc, 1, 12, ByteOrder::BigEndian).is_ok());
+    assert!(bf.retrieve_u64(1, 12).unwrap() == 0xabc);
+
+    // little-endian bytes in an LSB0 object are the same as insert_u64
+    let mut bf = BitField::with_capacity(3).with_bit_order(BitOrder::Lsb0Words(1));
+    assert!(bf.insert_endian(0x1234, 4, 19, ByteOrder::LittleEndian).is_ok());
+    assert!(bf.retrieve_u64(4, 19).unwrap() == 0x1234);
+    assert!(bf.retrieve_endian(4, 19, ByteOrder::BigEndian).unwrap() == 0x3412);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_insert_endian_errors()
+{
+    let mut bf = BitField::with_capacity(2);
+
+    assert!(bf.insert_endian(0xffff, 4, 19, ByteOrder::LittleEndian) ==
+            Err(BitFieldError::InvalidIndex{start_bit: 4, stop_bit: 19,
+                                            bit_len: 16}));
+    assert!(bf.retrieve_u64(0, 15).unwrap() == 0);
+    assert!(bf.insert_endian(0, 4, 3, ByteOrder::Pdp) ==
+            Err(BitFieldError::NegativeRange{start_bit: 4, stop_bit: 3}));
+    assert!(bf.retrieve_endian(0, 15, ByteOrder::Pdp).unwrap() == 0);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_single_bit()
+{
+    let mut bf = BitField::with_capacity(2);
+
+    assert!(bf.set_bit(0).is_ok());
+    assert!(bf.set_bit(9).is_ok());
+    assert!(bf.as_slice() == [0x80, 0x40]);
+    assert!(bf.get_bit(0).unwrap());
+    assert!(!bf.get_bit(1).unwrap());
+    assert!(bf.get_bit(9).unwrap());
+
+    assert!(bf.toggle_bit(0).is_ok());
+    assert!(bf.toggle_bit(15).is_ok());
+    assert!(bf.as_slice() == [0x00, 0x41]);
+    assert!(bf.clear_bit(9).is_ok());
+    assert!(bf.assign_bit(3, true).is_ok());
+    assert!(bf.assign_bit(15, false).is_ok());
+    assert!(bf.as_slice() == [0x10, 0x00]);
+
+    assert!(bf.get_bit(16) ==
+            Err(BitFieldError::InvalidIndex{start_bit: 16, stop_bit: 16,
+                                            bit_len: 16}));
+    assert!(bf.set_bit(16) ==
+            Err(BitFieldError::InvalidIndex{start_bit: 16, stop_bit: 16,
+                                            bit_len: 16}));
+    assert!(bf.clear_bit(usize::MAX) ==
+            Err(BitFieldError::InvalidIndex{start_bit: usize::MAX,
+                                            stop_bit: usize::MAX,
+                                            bit_len: 16}));
+
+    let mut bf = BitField::with_capacity(2).with_bit_order(BitOrder::Lsb0);
+    assert!(bf.set_bit(0).is_ok());
+    assert!(bf.set_bit(9).is_ok());
+    assert!(bf.as_slice() == [0x02, 0x01]);
+    assert!(bf.get_bit(9).unwrap());
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_range_fill()
+{
+    let mut bf = BitField::with_capacity(4);
+
+    assert!(bf.set_range(3, 28).is_ok());
+    // Offset  Binary                                Hex
+    // =======================================================
+    // 000000: 00011111 11111111 11111111 11111000   1fff fff8
+    assert!(bf.retrieve_u64(0, 31).unwrap() == 0x1ffffff8);
+
+    assert!(bf.clear_range(8, 15).is_ok());
+    assert!(bf.retrieve_u64(0, 31).unwrap() == 0x1f00fff8);
+
+    assert!(bf.toggle_range(0, 31).is_ok());
+    assert!(bf.retrieve_u64(0, 31).unwrap() == 0xe0ff0007);
+
+    assert!(bf.toggle_range(4, 4).is_ok());
+    assert!(bf.retrieve_u64(0, 31).unwrap() == 0xe8ff0007);
+
+    assert!(bf.set_range(4, 3) ==
+            Err(BitFieldError::NegativeRange{start_bit: 4, stop_bit: 3}));
+    assert!(bf.clear_range(0, 32) ==
+            Err(BitFieldError::InvalidIndex{start_bit: 0, stop_bit: 32,
+                                            bit_len: 32}));
+    assert!(bf.retrieve_u64(0, 31).unwrap() == 0xe8ff0007);
+
+    let mut bf = BitField::with_capacity(4).with_bit_order(BitOrder::Lsb0Words(4));
+    assert!(bf.set_range(3, 28).is_ok());
+    assert!(bf.as_slice() == [0xf8, 0xff, 0xff, 0x1f]);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_get_set_ranges()
+{
+    let mut bf = BitField::with_capacity(4);
+
+    assert!(bf.set(4..=15, 0xabcu16).is_ok());
+    assert!(bf.get::<u16, _>(4..16).unwrap() == 0xabc);
+    assert!(bf.get::<u64, _>(..).unwrap() == 0x0abc0000);
+    assert!(bf.get::<u8, _>(..8).unwrap() == 0x0a);
+    assert!(bf.get::<u8, _>(..=7).unwrap() == 0x0a);
+    assert!(bf.get::<u16, _>(16..).unwrap() == 0);
+
+    assert!(bf.set(20.., -1i16).is_ok());
+    assert!(bf.get::<i16, _>(20..).unwrap() == -1);
+    assert!(bf.get::<u16, _>(20..).unwrap() == 0xfff);
+
+    assert!(bf.set(0..1, true).is_ok());
+    assert!(bf.get::<bool, _>(0..1).unwrap());
+    assert!(bf.get::<bool, _>(1..=1) == Ok(false));
+
+    let bounds = (std::ops::Bound::Excluded(3), std::ops::Bound::Included(7));
+    assert!(bf.get::<u8, _>(bounds).unwrap() == 0xa);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_get_set_range_errors()
+{
+    let mut bf = BitField::with_capacity(4);
+
+    assert!(bf.set(4..4, 1u8) ==
+            Err(BitFieldError::NegativeRange{start_bit: 4, stop_bit: 3}));
+    assert!(bf.set(0..0, 1u8) == Err(BitFieldError::EmptyRange{start_bit: 0}));
+    let (start, stop) = (8, 4);
+    assert!(bf.get::<u8, _>(start..=stop) ==
+            Err(BitFieldError::NegativeRange{start_bit: 8, stop_bit: 4}));
+    assert!(bf.set(0..9, 1u8) ==
+            Err(BitFieldError::ExceededDataRange{width: 9, max_width: 8}));
+    assert!(bf.get::<u64, _>(24..40) ==
+            Err(BitFieldError::InvalidIndex{start_bit: 24, stop_bit: 39,
+                                            bit_len: 32}));
+    assert!(bf.set(4..=11, -128i16).is_ok());
+    assert!(bf.set(4..=11, -129i16) == Err(BitFieldError::Overflow{width: 8}));
+    assert!(bf.set(0..=2, 4i8) == Err(BitFieldError::Overflow{width: 3}));
+
+    // no wrapping at the extremes of usize
+    assert!(bf.get::<u8, _>(usize::MAX..) ==
+            Err(BitFieldError::NegativeRange{start_bit: usize::MAX,
+                                             stop_bit: 31}));
+    assert!(bf.get::<u8, _>(0..=usize::MAX) ==
+            Err(BitFieldError::InvalidIndex{start_bit: 0, stop_bit: usize::MAX,
+                                            bit_len: 32}));
+    assert!(bf.insert_u32(1, 9, 3) ==
+            Err(BitFieldError::NegativeRange{start_bit: 9, stop_bit: 3}));
+    assert!(bf.retrieve_u16(9, 3) ==
+            Err(BitFieldError::NegativeRange{start_bit: 9, stop_bit: 3}));
+    assert!(BitField::new().get::<u8, _>(..) ==
+            Err(BitFieldError::InvalidIndex{start_bit: 0, stop_bit: 0,
+                                            bit_len: 0}));
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_bit_length()
+{
+    let mut bf = BitField::with_bit_capacity(37);
+
+    assert!(bf.bit_len() == 37);
+    assert!(bf.len() == 5);
+    assert!(bf != BitField::with_bit_capacity(40));
+
+    assert!(bf.set_range(0, 36).is_ok());
+    assert!(bf.as_slice() == [0xff, 0xff, 0xff, 0xff, 0xf8]);
+    assert!(bf.set_bit(37) ==
+            Err(BitFieldError::InvalidIndex{start_bit: 37, stop_bit: 37,
+                                            bit_len: 37}));
+    assert!(bf.get::<u8, _>(32..).unwrap() == 0x1f);
+    assert!(bf.insert_u64(0, 30, 38) ==
+            Err(BitFieldError::InvalidIndex{start_bit: 30, stop_bit: 38,
+                                            bit_len: 37}));
+
+    println!("{}", bf.debug_string());
+    // Offset  Binary                                Hex
+    // =======================================================
+    // 000000: 11111111 11111111 11111111 11111111   ffff ffff
+    // 000004: 11111---                              f8
+    assert!(bf.debug_string().contains("\n000004: 11111--- "));
+
+    bf.truncate_bits(12);
+    assert!(bf.bit_len() == 12);
+    assert!(bf.as_slice() == [0xff, 0xf0]);
+    bf.truncate_bits(20);
+    assert!(bf.bit_len() == 12);
+
+    bf.resize_bits(20);
+    assert!(bf.as_slice() == [0xff, 0xf0, 0x00]);
+    assert!(bf.get::<u32, _>(..).unwrap() == 0xfff00);
+
+    bf.grow(1);
+    assert!(bf.bit_len() == 28);
+    bf.clear();
+    assert!(bf.is_empty());
+    assert!(bf.bit_len() == 0);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_bit_length_lsb0()
+{
+    let mut bf = BitField::with_bit_capacity(12).with_bit_order(BitOrder::Lsb0);
+
+    assert!(bf.set_range(0, 11).is_ok());
+    assert!(bf.as_slice() == [0x0f, 0xff]);
+
+    bf.resize_bits(20);
+    assert!(bf.as_slice() == [0x00, 0x0f, 0xff]);
+    assert!(bf.get::<u32, _>(..).unwrap() == 0xfff);
+
+    bf.truncate_bits(4);
+    assert!(bf.as_slice() == [0x0f]);
+
+    // changing order moves the padding
+    let mut bf = BitField::with_bit_capacity(4);
+    assert!(bf.set_range(0, 3).is_ok());
+    bf.set_bit_order(BitOrder::Lsb0);
+    assert!(bf.as_slice() == [0x00]);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_bit_length_operators()
+{
+    let mut bf = BitField::with_bit_capacity(12);
+    assert!(bf.insert_u64(0xa5a, 0, 11).is_ok());
+
+    let inverted = !&bf;
+    assert!(inverted.bit_len() == 12);
+    assert!(inverted.as_slice() == [0x5a, 0x50]);
+    assert!(!inverted == bf);
+
+    assert!((&bf >> 4).as_slice() == [0x0a, 0x50]);
+    assert!((&bf << 4).as_slice() == [0x5a, 0x00]);
+    assert!((&bf << 12).as_slice() == [0x00, 0x00]);
+
+    // operands are aligned on their least significant bits
+    let mut other = BitField::with_bit_capacity(4);
+    assert!(other.insert_u64(0xf, 0, 3).is_ok());
+    let result = &bf | &other;
+    assert!(result.bit_len() == 12);
+    assert!(result.get::<u16, _>(..).unwrap() == 0xa5f);
+    assert!((&other & &bf).get::<u16, _>(..).unwrap() == 0x00a);
+    assert!((&bf ^ &other).get::<u16, _>(..).unwrap() == 0xa55);
+
+    let empty = BitField::new();
+    assert!((&empty & &bf).get::<u16, _>(..).unwrap() == 0);
+    assert!((&empty | &bf) == bf);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_push_pop()
+{
+    let mut bf = BitField::new();
+
+    bf.push_bit(true);
+    assert!(bf.push_bits(0x5, 3).is_ok());
+    assert!(bf.push_bits(0xabc, 12).is_ok());
+    assert!(bf.push_bits(0, 0).is_ok());
+    bf.push_bit(false);
+    bf.push_bit(true);
+
+    assert!(bf.bit_len() == 18);
+    // Offset  Binary                                Hex
+    // =======================================================
+    // 000000: 11011010 10111100 01------            dabc 40
+    assert!(bf.as_slice() == [0xda, 0xbc, 0x40]);
+    assert!(bf.retrieve_u64(4, 15).unwrap() == 0xabc);
+
+    assert!(bf.pop_bits(2).unwrap() == 0x1);
+    assert!(bf.pop_bits(12).unwrap() == 0xabc);
+    assert!(bf.bit_len() == 4);
+    assert!(bf.as_slice() == [0xd0]);
+    assert!(bf.pop_bits(5) == Err(BitFieldError::ExceededDataRange{width: 5, max_width: 4}));
+    assert!(bf.pop_bits(65) ==
+            Err(BitFieldError::ExceededDataRange{width: 65, max_width: 64}));
+    assert!(bf.push_bits(0, 65) ==
+            Err(BitFieldError::ExceededDataRange{width: 65, max_width: 64}));
+    assert!(bf.pop_bits(4).unwrap() == 0xd);
+    assert!(bf.is_empty());
+    assert!(bf.as_slice().is_empty());
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_extend_from_bitslice()
+{
+    let mut header = BitField::new();
+    assert!(header.push_bits(0x3, 2).is_ok());
+
+    let mut payload = BitField::with_bit_capacity(10);
+    assert!(payload.insert_u64(0x2aa, 0, 9).is_ok());
+
+    header.extend_from_bitslice(&payload);
+    header.extend_from_bitslice(&BitField::new());
+    assert!(header.bit_len() == 12);
+    assert!(header.retrieve_u64(0, 11).unwrap() == 0xeaa);
+
+    // an LSB0 builder places each pushed field's least significant bit first
+    let mut bf = BitField::new().with_bit_order(BitOrder::Lsb0Words(1));
+    assert!(bf.push_bits(0x5, 4).is_ok());
+    assert!(bf.push_bits(0xa, 4).is_ok());
+    assert!(bf.push_bits(0x1, 1).is_ok());
+    assert!(bf.as_slice() == [0xa5, 0x01]);
+    assert!(bf.pop_bits(5).unwrap() == 0x1a);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_bitwise_alignment()
+{
+    let bf1 = BitField::from_slice(&[0xa5, 0xa5]);
+    let bf2 = BitField::from_slice(&[0xcc, 0xcc, 0xcc, 0xcc]);
+
+    let result = bf1.and_aligned(&bf2, Alignment::Left).unwrap();
+    // Offset  Binary                                Hex
+    // =======================================================
+    // 000000: 10000100 10000100 00000000 00000000   8484 0000
+    assert!(result.as_slice() == [0x84, 0x84, 0x00, 0x00]);
+    assert!(bf2.and_aligned(&bf1, Alignment::Left).unwrap() == result);
+
+    let result = bf1.or_aligned(&bf2, Alignment::Left).unwrap();
+    assert!(result.as_slice() == [0xed, 0xed, 0xcc, 0xcc]);
+    let result = bf1.xor_aligned(&bf2, Alignment::Left).unwrap();
+    assert!(result.as_slice() == [0x69, 0x69, 0xcc, 0xcc]);
+
+    let result = bf1.and_aligned(&bf2, Alignment::Right).unwrap();
+    assert!(result == &bf1 & &bf2);
+    assert!(result.as_slice() == [0x00, 0x00, 0x84, 0x84]);
+
+    assert!(bf1.and_aligned(&bf2, Alignment::Strict) ==
+            Err(BitFieldError::LengthMismatch{left_bits: 16, right_bits: 32}));
+    assert!(bf1.or_aligned(&bf1, Alignment::Strict).unwrap() == bf1);
+
+    // left alignment at bit granularity
+    let mut bf3 = BitField::with_bit_capacity(3);
+    assert!(bf3.set_range(0, 2).is_ok());
+    let result = bf3.xor_aligned(&bf1, Alignment::Left).unwrap();
+    assert!(result.as_slice() == [0x45, 0xa5]);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_bitwise_empty_operands()
+{
+    let empty = BitField::new();
+    let bf = BitField::from_slice(&[0xa5]);
+
+    assert!((&empty & &empty).is_empty());
+    assert!((&empty | &empty).is_empty());
+    assert!((&bf & &empty).as_slice() == [0x00]);
+    assert!((&empty ^ &bf) == bf);
+    assert!(empty.or_aligned(&bf, Alignment::Left).unwrap() == bf);
+    assert!(empty.and_aligned(&empty, Alignment::Strict).unwrap().is_empty());
+    assert!(empty.and_aligned(&bf, Alignment::Strict) ==
+            Err(BitFieldError::LengthMismatch{left_bits: 0, right_bits: 8}));
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_compound_assignment()
+{
+    let bf1 = BitField::from_slice(&[0xa5, 0xa5, 0xa5, 0xa5]);
+    let bf2 = BitField::from_slice(&[0xcc, 0xcc, 0xcc, 0xcc]);
+
+    let mut result = bf1.clone();
+    result &= &bf2;
+    assert!(result == &bf1 & &bf2);
+
+    let mut result = bf1.clone();
+    result |= bf2.clone();
+    assert!(result == &bf1 | &bf2);
+
+    let mut result = bf1.clone();
+    result ^= &bf2;
+    assert!(result.retrieve_u64(0, 31).unwrap() == 0x69696969);
+
+    // mismatched lengths behave as the operators do
+    let short = BitField::from_slice(&[0x0f]);
+    let mut result = short.clone();
+    result |= &bf2;
+    assert!(result == &short | &bf2);
+    assert!(result.as_slice() == [0xcc, 0xcc, 0xcc, 0xcf]);
+
+    let mut result = bf1.clone();
+    result.invert();
+    assert!(result == !&bf1);
+
+    let mut result = bf1.clone();
+    result >>= 7;
+    assert!(result.retrieve_u64(0, 31).unwrap() == 0x014b4b4b);
+    result <<= 7;
+    assert!(result.retrieve_u64(0, 31).unwrap() == 0xa5a5a580);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_shift_amounts()
+{
+    let bf = BitField::from_slice(&[0xa5, 0xa5, 0xa5]);
+
+    assert!((&bf << 0) == bf);
+    assert!((&bf >> 0) == bf);
+    assert!((&bf << 8).as_slice() == [0xa5, 0xa5, 0x00]);
+    assert!((&bf >> 16).as_slice() == [0x00, 0x00, 0xa5]);
+    assert!((&bf << 23).as_slice() == [0x80, 0x00, 0x00]);
+    assert!((&bf >> 24).as_slice() == [0x00, 0x00, 0x00]);
+    assert!((&bf << usize::MAX).as_slice() == [0x00, 0x00, 0x00]);
+    assert!((&bf >> usize::MAX).as_slice() == [0x00, 0x00, 0x00]);
+    assert!((BitField::new() << 3).is_empty());
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_shift_matches_bitwise()
+{
+    let orders = [BitOrder::Msb0, BitOrder::Lsb0, BitOrder::Lsb0Words(4)];
+    let bytes: Vec<u8> = (0..37).map(|i| (i * 97 + 13) as u8).collect();
+
+    for order in orders
+    {
+        for bits in [1, 7, 64, 65, 131, 296]
+        {
+            let mut bf = BitField::from_slice(&bytes).with_bit_order(order);
+            bf.truncate_bits(bits);
+
+            for shift in [0, 1, 3, 8, 9, 63, 64, 71, bits - 1, bits, bits + 5]
+            {
+                let left = &bf << shift;
+                let right = &bf >> shift;
+
+                for i in 0..bits
+                {
+                    // towards the most significant bit, which is bit 0 in MSB0
+                    let (from_left, from_right) = match order
+                    {
+                        BitOrder::Msb0 => (i.checked_add(shift), i.checked_sub(shift)),
+                        _ => (i.checked_sub(shift), i.checked_add(shift))
+                    };
+                    let expected = |from: Option<usize>| from.filter(|&j| j < bits)
+                                                             .is_some_and(|j| bf.get_bit(j).unwrap());
+
+                    assert!(left.get_bit(i).unwrap() == expected(from_left));
+                    assert!(right.get_bit(i).unwrap() == expected(from_right));
+                }
+            }
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_rotate()
+{
+    let mut bf = BitField::from_slice(&[0xa5, 0x0f]);
+
+    bf.rotate_left(4);
+    assert!(bf.as_slice() == [0x50, 0xfa]);
+    bf.rotate_right(20);
+    assert!(bf.as_slice() == [0xa5, 0x0f]);
+
+    // only the 12 bits of the field rotate, not the padding
+    bf.truncate_bits(12);
+    bf.rotate_left(1);
+    assert!(bf.as_slice() == [0x4a, 0x10]);
+    bf.rotate_right(1);
+    assert!(bf.as_slice() == [0xa5, 0x00]);
+
+    // LSB0 rotates towards the highest numbered bit
+    let mut lsb0 = BitField::from_slice(&[0x81]).with_bit_order(BitOrder::Lsb0);
+    lsb0.rotate_left(1);
+    assert!(lsb0.as_slice() == [0x03]);
+
+    let mut empty = BitField::new();
+    empty.rotate_left(3);
+    assert!(empty.is_empty());
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_rotate_range()
+{
+    let mut bf = BitField::from_slice(&[0xa5, 0x0f]);
+
+    // bits 4 to 11 hold 0x50, rotated they hold 0x05
+    assert!(bf.rotate_range_left(4..12, 4).is_ok());
+    assert!(bf.as_slice() == [0xa0, 0x5f]);
+    assert!(bf.rotate_range_right(4..=11, 12).is_ok());
+    assert!(bf.as_slice() == [0xa5, 0x0f]);
+    assert!(bf.rotate_range_left(.., 8).is_ok());
+    assert!(bf.as_slice() == [0x0f, 0xa5]);
+
+    assert!(bf.rotate_range_left(8..17, 1) ==
+            Err(BitFieldError::InvalidIndex{start_bit: 8, stop_bit: 16,
+                                            bit_len: 16}));
+    assert!(bf.as_slice() == [0x0f, 0xa5]);
+
+    let orders = [BitOrder::Msb0, BitOrder::Lsb0, BitOrder::Lsb0Words(4)];
+    let bytes: Vec<u8> = (0..9).map(|i| (i * 97 + 13) as u8).collect();
+
+    for order in orders
+    {
+        let bf = BitField::from_slice(&bytes).with_bit_order(order);
+
+        for (start, stop, rotate) in [(0, 71, 5), (3, 66, 17), (9, 9, 1), (60, 70, 100)]
+        {
+            let mut rotated = bf.clone();
+            assert!(rotated.rotate_range_left(start..=stop, rotate).is_ok());
+
+            let width = stop - start + 1;
+            for i in 0..bf.bit_len()
+            {
+                // towards the most significant bit, which is bit 0 in MSB0
+                let from = if i < start || i > stop
+                {
+                    i
+                }
+                else if order == BitOrder::Msb0
+                {
+                    start + (i - start + rotate) % width
+                }
+                else
+                {
+                    start + (i - start + width - rotate % width) % width
+                };
+
+                assert!(rotated.get_bit(i).unwrap() == bf.get_bit(from).unwrap());
+            }
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_bit_queries()
+{
+    // 0         1
+    // 0123456789012345
+    // 0010010100001000
+    let mut bf = BitField::from_slice(&[0x25, 0x08]);
+
+    assert!(bf.count_ones() == 4);
+    assert!(bf.count_zeros() == 12);
+    assert!(bf.leading_zeros() == 2);
+    assert!(bf.trailing_zeros() == 3);
+    assert!(bf.first_set() == Some(2));
+    assert!(bf.last_set() == Some(12));
+    assert!(!bf.parity());
+
+    assert!(bf.count_ones_range(3..12) == Ok(2));
+    assert!(bf.count_zeros_range(3..12) == Ok(7));
+    assert!(bf.leading_zeros_range(3..12) == Ok(2));
+    assert!(bf.trailing_zeros_range(3..12) == Ok(4));
+    assert!(bf.first_set_range(3..12) == Ok(Some(5)));
+    assert!(bf.last_set_range(3..12) == Ok(Some(7)));
+    assert!(bf.parity_range(3..=12) == Ok(true));
+    assert!(bf.first_set_range(13..) == Ok(None));
+    assert!(bf.count_ones_range(8..17) ==
+            Err(BitFieldError::InvalidIndex{start_bit: 8, stop_bit: 16,
+                                            bit_len: 16}));
+
+    bf.truncate_bits(12);
+    assert!(bf.count_zeros() == 9);
+    assert!(bf.trailing_zeros() == 4);
+    assert!(bf.parity());
+
+    // LSB0 counts leading zeros from the highest numbered bit
+    let lsb0 = BitField::from_slice(&[0x25, 0x08]).with_bit_order(BitOrder::Lsb0);
+    assert!(lsb0.leading_zeros() == 2);
+    assert!(lsb0.trailing_zeros() == 3);
+    assert!(lsb0.first_set() == Some(3));
+    assert!(lsb0.last_set() == Some(13));
+
+    let empty = BitField::new();
+    assert!(empty.count_ones() == 0);
+    assert!(empty.leading_zeros() == 0);
+    assert!(empty.first_set().is_none());
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_bit_queries_match_bitwise()
+{
+    let orders = [BitOrder::Msb0, BitOrder::Lsb0, BitOrder::Lsb0Words(4)];
+    let bytes: Vec<u8> = (0..21).map(|i| if i % 5 == 0 { 0 } else { (i * 97 + 13) as u8 }).collect();
+
+    for order in orders
+    {
+        let mut bf = BitField::from_slice(&bytes).with_bit_order(order);
+        bf.truncate_bits(163);
+
+        for (start, stop) in [(0, 162), (1, 1), (0, 63), (5, 140), (64, 127), (80, 86), (150, 162)]
+        {
+            let set: Vec<usize> = (start..=stop).filter(|&i| bf.get_bit(i).unwrap()).collect();
+            let width = stop - start + 1;
+            let msb0 = order == BitOrder::Msb0;
+            let (leading, trailing) = match (set.first(), set.last())
+            {
+                (Some(&first), Some(&last)) if msb0 => (first - start, stop - last),
+                (Some(&first), Some(&last)) => (stop - last, first - start),
+                _ => (width, width)
+            };
+
+            assert!(bf.count_ones_range(start..=stop) == Ok(set.len()));
+            assert!(bf.count_zeros_range(start..=stop) == Ok(width - set.len()));
+            assert!(bf.parity_range(start..=stop) == Ok(set.len() % 2 == 1));
+            assert!(bf.first_set_range(start..=stop) == Ok(set.first().copied()));
+            assert!(bf.last_set_range(start..=stop) == Ok(set.last().copied()));
+            assert!(bf.leading_zeros_range(start..=stop) == Ok(leading));
+            assert!(bf.trailing_zeros_range(start..=stop) == Ok(trailing));
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_iterators()
+{
+    // 0         1
+    // 0123456789012345
+    // 0010010100001000
+    let bf = BitField::from_slice(&[0x25, 0x08]);
+
+    let bits: Vec<bool> = bf.iter().collect();
+    assert!(bits.len() == 16 && bf.iter().len() == 16);
+    assert!(bits[2] && bits[5] && !bits[6] && bits[12]);
+    assert!(bf.iter().rev().position(|b| b) == Some(3));
+    assert!((&bf).into_iter().filter(|&b| b).count() == 4);
+
+    assert!(bf.iter_ones().collect::<Vec<_>>() == [2, 5, 7, 12]);
+    assert!(bf.iter_ones().rev().collect::<Vec<_>>() == [12, 7, 5, 2]);
+    assert!(bf.iter_zeros().count() == 12);
+    assert!(bf.iter_zeros().take(3).collect::<Vec<_>>() == [0, 1, 3]);
+
+    let mut ones = bf.iter_ones();
+    assert!(ones.next() == Some(2) && ones.next_back() == Some(12));
+    assert!(ones.collect::<Vec<_>>() == [5, 7]);
+
+    // 001001 010000 1000
+    assert!(bf.chunks(6).collect::<Vec<_>>() == [0x09, 0x10, 0x08]);
+    assert!(bf.chunks(6).rev().collect::<Vec<_>>() == [0x08, 0x10, 0x09]);
+    assert!(bf.chunks(16).collect::<Vec<_>>() == [0x2508]);
+    assert!(bf.chunks(3).len() == 6);
+
+    assert!(bf.windows(12).collect::<Vec<_>>() == [0x250, 0x4a1, 0x942, 0x284, 0x508]);
+    assert!(bf.windows(14).rev().collect::<Vec<_>>() == [0x2508, 0x1284, 0x0942]);
+    assert!(bf.windows(17).next().is_none());
+
+    let empty = BitField::new();
+    assert!(empty.iter().next().is_none());
+    assert!(empty.iter_ones().next_back().is_none());
+    assert!(empty.chunks(8).next().is_none());
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_from_iterator()
+{
+    let bf: BitField = [false, false, true, false, false, true, false, true, true].into_iter().collect();
+
+    assert!(bf.bit_len() == 9);
+    assert!(bf.as_slice() == [0x25, 0x80]);
+
+    let mut lsb0 = BitField::new().with_bit_order(BitOrder::Lsb0);
+    lsb0.extend(bf.iter());
+    assert!(lsb0.iter().eq(bf.iter()));
+    assert!(lsb0.as_slice() == [0x01, 0xa4]);
+
+    for order in [BitOrder::Msb0, BitOrder::Lsb0, BitOrder::Lsb0Words(4)]
+    {
+        let bytes: Vec<u8> = (0..21).map(|i| (i * 97 + 13) as u8).collect();
+        let mut bf = BitField::from_slice(&bytes).with_bit_order(order);
+        bf.truncate_bits(163);
+
+        let ones: Vec<usize> = bf.iter().enumerate().filter(|&(_, b)| b).map(|(i, _)| i).collect();
+        let zeros: Vec<usize> = bf.iter().enumerate().filter(|&(_, b)| !b).map(|(i, _)| i).collect();
+
+        assert!(bf.iter_ones().collect::<Vec<_>>() == ones);
+        assert!(bf.iter_zeros().rev().collect::<Vec<_>>() == zeros.iter().rev().copied().collect::<Vec<_>>());
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+#[should_panic]
+fn bitfield_chunks_zero_width()
+{
+    let _ = BitField::from_slice(&[0xa5]).chunks(0);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_find_pattern()
+{
+    // 0         1         2         3
+    // 01234567890123456789012345678901
+    // 00011010110011111111110000011101
+    let bf = BitField::from_slice(&[0x1a, 0xcf, 0xfc, 0x1d]);
+    let mut marker = BitField::new();
+    assert!(marker.push_bits(0b1101, 4).is_ok());
+
+    assert!(bf.find_pattern(&marker) == Some(3));
+    assert!(bf.rfind_pattern(&marker) == Some(28));
+    assert!(bf.find_all_patterns(&marker) == [3, 28]);
+    assert!(bf.find_all_patterns_within(&marker, 1) == [3, 5, 8, 9, 12, 13, 14, 15, 16, 17, 18, 20, 28]);
+    assert!(bf.find_pattern(&bf) == Some(0));
+    assert!(bf.find_pattern(&BitField::new()).is_none());
+    assert!(marker.find_pattern(&bf).is_none());
+
+    // patterns in another bit order are compared bit number by bit number
+    let lsb0 = BitField::from_slice(&[0x0b]).with_bit_order(BitOrder::Lsb0);
+    let mut lsb0_marker = lsb0.clone();
+    lsb0_marker.truncate_bits(4);
+    assert!(bf.find_all_patterns(&lsb0_marker) == [3, 28]);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_find_pattern_matches_bitwise()
+{
+    let bytes: Vec<u8> = (0..40).map(|i| (i * 97 + 13) as u8).collect();
+
+    for order in [BitOrder::Msb0, BitOrder::Lsb0, BitOrder::Lsb0Words(4)]
+    {
+        let bf = BitField::from_slice(&bytes).with_bit_order(order);
+
+        for (start, len) in [(0, 1), (5, 7), (100, 24), (200, 64), (37, 70), (150, 170)]
+        {
+            let pattern: BitField = bf.iter().skip(start).take(len).collect();
+
+            for max_errors in [0, 2]
+            {
+                let expected: Vec<usize> = (0..=bf.bit_len() - len).filter(|&offset|
+                    pattern.iter().zip(bf.iter().skip(offset)).filter(|(a, b)| a != b).count() <= max_errors)
+                    .collect();
+
+                assert!(expected.contains(&start));
+                assert!(bf.find_all_patterns_within(&pattern, max_errors) == expected);
+                assert!(bf.find_pattern_within(&pattern, max_errors) == expected.first().copied());
+                assert!(bf.rfind_pattern_within(&pattern, max_errors) == expected.last().copied());
+            }
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitslice_matches_bitfield()
+{
+    let bytes: Vec<u8> = (0..24).map(|i| (i * 97 + 13) as u8).collect();
+
+    for order in [BitOrder::Msb0, BitOrder::Lsb0, BitOrder::Lsb0Words(4)]
+    {
+        let mut bf = BitField::from_slice(&bytes).with_bit_order(order);
+        bf.truncate_bits(181);
+
+        for (start, stop) in [(0, 180), (3, 90), (64, 127), (77, 77), (100, 180)]
+        {
+            let view = bf.slice(start..=stop).unwrap();
+            let width = stop - start + 1;
+
+            assert!(view.bit_len() == width);
+            assert!(view.iter().eq(bf.iter().skip(start).take(width)));
+            assert!(view.count_ones() == bf.count_ones_range(start..=stop).unwrap());
+            assert!(view.first_set().map(|b| b + start) == bf.first_set_range(start..=stop).unwrap());
+            assert!(view.last_set().map(|b| b + start) == bf.last_set_range(start..=stop).unwrap());
+            assert!(view.leading_zeros() == bf.leading_zeros_range(start..=stop).unwrap());
+            assert!(view.to_bitfield().iter().eq(view.iter()));
+
+            let bits = cmp::min(width, 64);
+            assert!(view.retrieve_u64(0, bits - 1) == bf.retrieve_u64(start, start + bits - 1));
+
+            let mut copy = bf.clone();
+            let value = 0x5a5a_a5a5_0ff0_f00fu64 >> (64 - bits);
+            assert!(copy.slice_mut(start..=stop).unwrap().insert_u64(value, 0, bits - 1).is_ok());
+
+            let mut expected = bf.clone();
+            assert!(expected.insert_u64(value, start, start + bits - 1).is_ok());
+            assert!(copy == expected);
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Runs the same inserts, retrieves and resizes on an object of any storage,
+// returning its contents in a Vec backed object.
+fn exercise_storage<S: BitStorage>(mut bf: BitField<S>, order: BitOrder) -> BitField
+{
+    bf.truncate_bits(0);
+    bf.set_bit_order(order);
+    assert!(bf.push_bits(0x1abcd, 17).is_ok());
+    bf.resize_bits(61);
+    assert!(bf.insert_u64(0x5a5, 20, 31).is_ok());
+    assert!(bf.insert_i16(-3, 40, 52).is_ok());
+    assert!(bf.retrieve_u64(0, 16) == Ok(0x1abcd));
+    assert!(bf.retrieve_u16(20, 31) == Ok(0x5a5));
+    assert!(bf.retrieve_i16(40, 52) == Ok(-3));
+    assert!(bf.count_ones() == 29);
+    bf.rotate_left(5);
+    bf.truncate_bits(50);
+    bf.to_bitfield()
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_storage_semantics()
+{
+    for order in [BitOrder::Msb0, BitOrder::Lsb0, BitOrder::Lsb0Words(4)]
+    {
+        let expected = exercise_storage(BitField::new(), order);
+        let mut borrowed = [0xffu8; 8];
+
+        assert!(expected.bit_len() == 50);
+        assert!(exercise_storage(BitField::from_storage([0xffu8; 8]), order) == expected);
+        assert!(exercise_storage(BitField::from_storage(&mut borrowed[..]), order) == expected);
+        assert!(exercise_storage(BitField::from_storage([0u32; 2]), order) == expected);
+        assert!(exercise_storage(BitField::from_storage([0u64; 1]), order) == expected);
+        assert!(exercise_storage(BitField::from_storage(InlineBytes::<4>::new()), order) == expected);
+    }
+
+    let mut bf = BitField::from_storage(InlineBytes::<2>::new());
+    bf.truncate_bits(12);
+    assert!(bf.set_bit(11).is_ok());
+    assert!(bf.clone().into_storage().is_inline());
+    bf.grow(1);
+    assert!(!bf.clone().into_storage().is_inline());
+    assert!(bf.as_slice() == [0x00, 0x10, 0x00]);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[cfg(feature = "std")]
+fn decode_header(bytes: &[u8]) -> std::io::Result<(u8, u16)>
+{
+    use std::io::Write;
+
+    let mut bf = BitField::new();
+    bf.write_all(bytes)?;
+
+    Ok((bf.retrieve_u8(0, 3)?, bf.retrieve_u16(4, 15)?))
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[cfg(feature = "std")]
+#[test]
+fn bitfield_std_integration()
+{
+    use std::io::{ErrorKind, Write};
+
+    assert!(decode_header(&[0x5a, 0xbc]).unwrap() == (0x5, 0xabc));
+
+    let error = decode_header(&[0x5a]).unwrap_err();
+    assert!(error.kind() == ErrorKind::InvalidData);
+    assert!(error.to_string() == "bits 4 to 15 are outside an object of 8 bits");
+
+    let error: Box<dyn std::error::Error> = BitFieldError::Overflow{width: 3}.into();
+    assert!(error.to_string() == "value does not fit in 3 bits");
+
+    let mut bf = BitField::from_storage([0u8; 2]);
+    bf.truncate_bits(4);
+    assert!(bf.write(&[0xab, 0xcd, 0xef]).unwrap() == 1);
+    assert!(bf.write_all(&[0x12]).unwrap_err().kind() == ErrorKind::WriteZero);
+    assert!(bf.as_slice() == [0x0a, 0xb0]);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_error_context()
+{
+    let mut bf = BitField::with_bit_capacity(20);
+
+    let error = bf.retrieve_u16(12, 23).unwrap_err();
+    assert!(error == BitFieldError::InvalidIndex{start_bit: 12, stop_bit: 23, bit_len: 20});
+    assert!(error.to_string() == "bits 12 to 23 are outside an object of 20 bits");
+
+    let error = bf.set_bit(20).unwrap_err();
+    assert!(error == BitFieldError::InvalidIndex{start_bit: 20, stop_bit: 20, bit_len: 20});
+    assert!(error.to_string() == "bit 20 is outside an object of 20 bits");
+
+    let (start, stop) = (9, 5);
+    let error = bf.get::<u8, _>(start..stop).unwrap_err();
+    assert!(error == BitFieldError::NegativeRange{start_bit: 9, stop_bit: 4});
+    assert!(error.to_string() == "range stops at bit 4 before it starts at bit 9");
+
+    let (start, stop) = (3, 0);
+    let error = bf.set(start..stop, 1u8).unwrap_err();
+    assert!(error.to_string() == "range starting at bit 3 ends before bit 0");
+
+    let error = bf.get::<u8, _>(0..12).unwrap_err();
+    assert!(error == BitFieldError::ExceededDataRange{width: 12, max_width: 8});
+    assert!(error.to_string() == "range of 12 bits is wider than the 8 bits allowed");
+
+    let error = bf.insert_i8(-5, 0, 2).unwrap_err();
+    assert!(error == BitFieldError::Overflow{width: 3});
+    assert!(error.to_string() == "value does not fit in 3 bits");
+
+    let error = bf.and_aligned(&BitField::with_capacity(1), Alignment::Strict).unwrap_err();
+    assert!(error == BitFieldError::LengthMismatch{left_bits: 20, right_bits: 8});
+    assert!(error.to_string() == "lengths of 20 and 8 bits differ");
+
+    let mut bf = BitField::from_storage([0u8; 1]);
+    let error = bf.push_bits(0, 4).unwrap_err();
+    assert!(error.to_string() == "storage of 8 bits can not hold 12 bits");
+
+    // errors from a view number bits from the start of the view
+    let bf = BitField::with_capacity(4);
+    let error = bf.slice(8..16).unwrap().get_bit(9).unwrap_err();
+    assert!(error == BitFieldError::InvalidIndex{start_bit: 9, stop_bit: 9, bit_len: 8});
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_overflow_policy()
+{
+    let mut bf = BitField::with_capacity(2);
+
+    // unsigned values wider than the range
+    assert!(bf.insert_u64_with(6, 0, 1, OverflowPolicy::Truncate).is_ok());
+    assert!(bf.retrieve_u8(0, 1).unwrap() == 2);
+    assert!(bf.insert_u64_with(6, 0, 1, OverflowPolicy::Checked) ==
+            Err(BitFieldError::Overflow{width: 2}));
+    assert!(bf.retrieve_u8(0, 1).unwrap() == 2);
+    assert!(bf.insert_u64_with(6, 0, 1, OverflowPolicy::Saturate).is_ok());
+    assert!(bf.retrieve_u8(0, 1).unwrap() == 3);
+    assert!(bf.set_with(4..8, 0x1fu8, OverflowPolicy::Saturate).is_ok());
+    assert!(bf.get::<u8, _>(4..8).unwrap() == 0xf);
+
+    // signed values outside the range
+    assert!(bf.insert_signed_with(-9, 0, 3, SignedEncoding::TwosComplement,
+                                  OverflowPolicy::Truncate).is_ok());
+    assert!(bf.retrieve_signed(0, 3, SignedEncoding::TwosComplement).unwrap() == 7);
+    assert!(bf.insert_signed_with(-9, 0, 3, SignedEncoding::SignMagnitude,
+                                  OverflowPolicy::Truncate).is_ok());
+    assert!(bf.retrieve_signed(0, 3, SignedEncoding::SignMagnitude).unwrap() == -1);
+    assert!(bf.insert_signed_with(-9, 0, 3, SignedEncoding::OnesComplement,
+                                  OverflowPolicy::Saturate).is_ok());
+    assert!(bf.retrieve_signed(0, 3, SignedEncoding::OnesComplement).unwrap() == -7);
+    assert!(bf.set_with(0..4, 100i8, OverflowPolicy::Saturate).is_ok());
+    assert!(bf.get::<i8, _>(0..4).unwrap() == 7);
+
+    // fixed-point values beyond the range
+    let q3_12 = FixedPoint::q(12);
+    assert!(bf.insert_fixed_with(8.0, 0, 15, &q3_12, OverflowPolicy::Checked) ==
+            Err(BitFieldError::Overflow{width: 16}));
+    assert!(bf.insert_fixed_with(8.0, 0, 15, &q3_12, OverflowPolicy::Truncate).is_ok());
+    assert!(bf.retrieve_fixed(0, 15, &q3_12).unwrap() == -8.0);
+    assert!(bf.insert_fixed_with(f64::INFINITY, 0, 15, &q3_12, OverflowPolicy::Truncate) ==
+            Err(BitFieldError::Overflow{width: 16}));
+
+    // range errors are reported before the value is checked
+    assert!(bf.insert_u64_with(6, 14, 17, OverflowPolicy::Checked) ==
+            Err(BitFieldError::InvalidIndex{start_bit: 14, stop_bit: 17,
+                                            bit_len: 16}));
+
+    // a policy set on the object applies to every insert and its views
+    let mut bf = BitField::with_capacity(2).with_overflow_policy(OverflowPolicy::Checked);
+    assert!(bf.overflow_policy() == Some(OverflowPolicy::Checked));
+    assert!(bf.insert_u64(6, 0, 1) == Err(BitFieldError::Overflow{width: 2}));
+    assert!(bf.set(0..4, 0x10u8) == Err(BitFieldError::Overflow{width: 4}));
+    assert!(bf.insert_endian(0x1ff, 0, 7, ByteOrder::LittleEndian) ==
+            Err(BitFieldError::Overflow{width: 8}));
+    assert!(bf.slice_mut(8..16).unwrap().insert_u64(0x100, 0, 7) ==
+            Err(BitFieldError::Overflow{width: 8}));
+    assert!(bf.insert_bytes(&[0xff, 0xff], 0, 3) ==
+            Err(BitFieldError::Overflow{width: 4}));
+    assert!(bf.insert_bytes(&[0x02, 0xff], 0, 8) ==
+            Err(BitFieldError::Overflow{width: 9}));
+    assert!(bf.as_slice() == [0, 0]);
+
+    bf.set_overflow_policy(OverflowPolicy::Saturate);
+    assert!(bf.insert_i8(-100, 0, 3).is_ok());
+    assert!(bf.retrieve_i8(0, 3).unwrap() == -8);
+    assert!(bf.insert_bytes(&[0x02, 0x00], 4, 12).is_ok());
+    assert!(bf.retrieve_u64(4, 12).unwrap() == 0x1ff);
+    assert!(bf.insert_bytes(&[0x01, 0x00], 4, 12).is_ok());
+    assert!(bf.retrieve_u64(0, 15).unwrap() == 0x8800);
+    assert!(bf.as_mut_bitslice().overflow_policy() == Some(OverflowPolicy::Saturate));
+
+    // a value that does not fit leaves the length unchanged
+    let mut bf = BitField::new().with_overflow_policy(OverflowPolicy::Checked);
+    assert!(bf.push_bits(0x1f, 4) == Err(BitFieldError::Overflow{width: 4}));
+    assert!(bf.bit_len() == 0);
+    assert!(bf.push_bits(0xf, 4).is_ok());
+    assert!(bf.bit_len() == 4);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_hex_dump()
+{
+    let mut bf = BitField::from_slice(&[0xff; 5]);
+    bf.truncate_bits(37);
+
+    // the last row lines its columns up with the full rows
+    assert_eq!(bf.debug_string(),
+               concat!("Offset  Binary                                Hex      \n",
+                       "=======================================================\n",
+                       "000000: 11111111 11111111 11111111 11111111   ffff ffff\n",
+                       "000004: 11111---                              f8\n"));
+
+    assert_eq!(bf.hex_dump().with_bytes_per_row(3)
+                            .with_offset(OffsetBase::Hex, 4)
+                            .with_ascii(true)
+                            .with_highlight(6..12)
+                            .to_string(),
+               concat!("Offset  Binary                       Hex       ASCII\n",
+                       "====================================================\n",
+                       "0000:   11111111 11111111 11111111   ffff ff   ...\n",
+                       "              ^^ ^^^^                ^^^^      ^^\n",
+                       "0003:   11111111 11111---            fff8      ..\n"));
+
+    // highlighted bits follow the bit order
+    let bf = BitField::with_capacity(2).with_bit_order(BitOrder::Lsb0);
+    assert_eq!(bf.hex_dump().with_header(false).with_bytes_per_row(2).with_highlight(..4).to_string(),
+               concat!("000000: 00000000 00000000   0000\n",
+                       "                     ^^^^     ^^\n"));
+
+    // a sub-range keeps the offsets of the object and its words
+    let bf = BitField::from_slice(&[0x34, 0x12, 0x78, 0x56, 0x9a])
+        .with_bit_order(BitOrder::Lsb0Words(2));
+    assert_eq!(bf.hex_dump().with_range(1..).with_binary(false).to_string(),
+               concat!("Offset  Hex      \n",
+                       "=================\n",
+                       "000001: 1256 789a\n"));
+
+    let bf = BitField::from_slice(b"A");
+    assert_eq!(bf.hex_dump().with_binary(false).with_ascii(true).with_bytes_per_row(1).to_string(),
+               concat!("Offset  Hex   ASCII\n",
+                       "===================\n",
+                       "000000: 41    A\n"));
+    assert_eq!(BitField::new().hex_dump().with_header(false).to_string(), "");
+
+    // output of xxd, xxd -c 4 -s 3 -l 20 and xxd -b
+    let bf = BitField::from_slice(b"Hello, world!\n\x00\x01\xff abcdefghijklmnopq");
+    assert_eq!(bf.hex_dump().xxd().to_string(),
+               concat!("00000000: 4865 6c6c 6f2c 2077 6f72 6c64 210a 0001  Hello, world!...\n",
+                       "00000010: ff20 6162 6364 6566 6768 696a 6b6c 6d6e  . abcdefghijklmn\n",
+                       "00000020: 6f70 71                                  opq\n"));
+    assert_eq!(bf.hex_dump().xxd().with_bytes_per_row(4).with_range(3..23).to_string(),
+               concat!("00000003: 6c6f 2c20  lo, \n",
+                       "00000007: 776f 726c  worl\n",
+                       "0000000b: 6421 0a00  d!..\n",
+                       "0000000f: 01ff 2061  .. a\n",
+                       "00000013: 6263 6465  bcde\n"));
+
+    let bf = BitField::from_slice(b"abcdefgh");
+    assert_eq!(bf.hex_dump().xxd().with_hex(false).with_binary(true).with_bytes_per_row(6)
+                 .to_string(),
+               concat!("00000000: 01100001 01100010 01100011 01100100 01100101 01100110  abcdef\n",
+                       "00000006: 01100111 01101000                                      gh\n"));
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_parse()
+{
+    // hex and binary vectors
+    let bf: BitField = "a5a5 a5a5".parse().unwrap();
+    assert!(bf == BitField::from_slice(&[0xa5; 4]));
+    assert!(BitField::from_hex_str(" 0xA5_c").unwrap().as_slice() == [0xa5, 0xc0]);
+    assert!(BitField::from_hex_str("a5c").unwrap().bit_len() == 12);
+
+    let bf: BitField = "0b1010_0101".parse().unwrap();
+    assert!(bf == BitField::from_slice(&[0xa5]));
+    let bf = BitField::from_binary_str("10110").unwrap();
+    assert!(bf.bit_len() == 5);
+    assert!(bf.as_slice() == [0xb0]);
+
+    assert!("".parse::<BitField>().unwrap().is_empty());
+    assert!(BitField::from_hex_str("0x").unwrap().is_empty());
+
+    // the Debug output reproduces any length and bit order
+    let mut bf = BitField::from_slice(&[0xa5, 0x5a, 0xc0]).with_bit_order(BitOrder::Lsb0Words(2));
+    bf.truncate_bits(18);
+    assert!(format!("{:?}", bf).parse::<BitField>().unwrap() == bf);
+    assert!(format!("{:#?}", bf).parse::<BitField>().unwrap() == bf);
+    assert!(format!("{:?}", BitField::new()).parse::<BitField>().unwrap() == BitField::new());
+
+    let bf = BitField::from_slice(&[0xa5, 0x5a, 0x01]);
+    assert!(bf.to_string().parse::<BitField>().unwrap() == bf);
+    assert!(format!("{:+#x}", bf).parse::<BitField>().unwrap() == bf);
+    assert!(format!("{:+#b}", bf).parse::<BitField>().unwrap() == bf);
+
+    // dumps, including a last row of padding bits
+    let mut bf = BitField::from_slice(&[0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc]);
+    bf.truncate_bits(45);
+    assert!(bf.debug_string().parse::<BitField>().unwrap() == bf);
+    assert!(bf.hex_dump().with_highlight(3..9).to_string().parse::<BitField>().unwrap() == bf);
+
+    let bf = BitField::from_slice(b"Hello, world!\n\x00\x01\xff abcdefghijklmnopq");
+    assert!(bf.hex_dump().xxd().to_string().parse::<BitField>().unwrap() == bf);
+    let dump = bf.hex_dump().xxd().with_range(3..7).to_string();
+    assert!(BitField::from_dump_str(&dump, BitOrder::Msb0) == Ok(BitField::from_slice(b"lo, ")));
+
+    // binary dumps of every order give back the length, hex ones whole bytes
+    let bytes = [0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0, 0x0f];
+
+    for order in [BitOrder::Msb0, BitOrder::Lsb0, BitOrder::Lsb0Words(2)]
+    {
+        for bits in [16, 37, 67, 72]
+        {
+            let mut bf = BitField::from_slice(&bytes).with_bit_order(order);
+            bf.truncate_bits(bits);
+
+            assert!(BitField::from_dump_str(&bf.debug_string(), order) == Ok(bf.clone()));
+            let dump = bf.hex_dump().with_highlight(3..20).with_bytes_per_row(3).to_string();
+            assert!(BitField::from_dump_str(&dump, order) == Ok(bf.clone()));
+
+            let mut whole = bf.clone();
+            whole.resize_bits(bf.len() * 8);
+            let dump = bf.hex_dump().with_binary(false).to_string();
+            assert!(BitField::from_dump_str(&dump, order) == Ok(whole));
+        }
+    }
+
+    // padding that is not beyond the length, as when a dump is read with
+    // the wrong order
+    let mut bf = BitField::from_slice(&bytes).with_bit_order(BitOrder::Lsb0);
+    bf.truncate_bits(37);
+    assert!(BitField::from_dump_str(&bf.debug_string(), BitOrder::Msb0) ==
+            Err(BitFieldError::Malformed{line: 3, column: 9}));
+
+    // the columns are read the same way in every row, and an xxd -g4 row of
+    // only 0 and 1 digits is still hex
+    let dump = "00000000: 00000000 00000001  ........";
+    assert!(BitField::from_dump_str(dump, BitOrder::Msb0) ==
+            Ok(BitField::from_slice(&[0, 0, 0, 0, 0, 0, 0, 1])));
+    let dump = "00000000: 00000000 00000001  ..";
+    assert!(BitField::from_dump_str(dump, BitOrder::Msb0) == Ok(BitField::from_slice(&[0, 1])));
+    let dump = "Offset  Hex\n0000: 10101010 01010101\n0004: 11001100";
+    assert!(BitField::from_dump_str(dump, BitOrder::Msb0) ==
+            Ok(BitField::from_slice(&[0x10, 0x10, 0x10, 0x10, 0x01, 0x01, 0x01, 0x01,
+                                      0x11, 0x00, 0x11, 0x00])));
+
+    // errors give the line and column of the first bad character
+    assert!("a5g5".parse::<BitField>() == Err(BitFieldError::Malformed{line: 1, column: 3}));
+    assert!(BitField::from_binary_str("0b10\n 012").unwrap_err() ==
+            BitFieldError::Malformed{line: 2, column: 4});
+    assert!("0b1102".parse::<BitField>().unwrap_err().to_string() ==
+            "text is malformed at line 1, column 6");
+
+    let error = "00000000: 4865 6c6c  Hel\n00000004: 6x6c  lo".parse::<BitField>();
+    assert!(error == Err(BitFieldError::Malformed{line: 2, column: 12}));
+    let error = "00000000: 4865 6c6  Hel".parse::<BitField>();
+    assert!(error == Err(BitFieldError::Malformed{line: 1, column: 19}));
+    let error = "000000: 1111---- 00000000".parse::<BitField>();
+    assert!(error == Err(BitFieldError::Malformed{line: 1, column: 13}));
+    let error = "000000: 11110000\nzz: 00000000".parse::<BitField>();
+    assert!(error == Err(BitFieldError::Malformed{line: 2, column: 1}));
+
+    let error = "BitField { bits: 6, order: Msb0, value: 0x7f }".parse::<BitField>();
+    assert!(error == Err(BitFieldError::Malformed{line: 1, column: 43}));
+    let error = "BitField { bits: 6, order: Msb0, value: 0x03f }".parse::<BitField>();
+    assert!(error == Err(BitFieldError::Malformed{line: 1, column: 45}));
+    let error = "BitField { bits: 6, order: Lsb1, value: 0x3f }".parse::<BitField>();
+    assert!(error == Err(BitFieldError::Malformed{line: 1, column: 28}));
+    let error = "BitField { bits: 8, order: Msb0, value: 0xff".parse::<BitField>();
+    assert!(error == Err(BitFieldError::Malformed{line: 1, column: 45}));
+}
@@ -0,0 +1,192 @@
+///////////////////////////////////////////////////////////////////////////////
+// The tests of fixed size storage and borrowed views, which need no
+// allocator, so run in every configuration, including:
+//     cargo test --no-default-features
+
+use bits::bitfield::{BitField, BitFieldError, BitOrder, OverflowPolicy};
+use bits::bitslice::{BitSlice, BitSliceMut};
+use bits::hexdump::{HexDump, OffsetBase};
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_array_storage()
+{
+    let mut bf = BitField::from_storage([0u8; 4]);
+
+    assert!(bf.bit_len() == 32);
+    assert!(bf.insert_u64(0xa5a5, 4, 19).is_ok());
+    assert!(bf.as_slice() == [0x0a, 0x5a, 0x50, 0x00]);
+    assert!(bf.retrieve_u16(4, 19) == Ok(0xa5a5));
+    assert!(bf.insert_i8(-3, 24, 27).is_ok());
+    assert!(bf.retrieve_i8(24, 27) == Ok(-3));
+    assert!(bf.count_ones() == 11);
+
+    bf >>= 4;
+    assert!(bf.as_slice() == [0x00, 0xa5, 0xa5, 0x0d]);
+    bf <<= 8;
+    assert!(bf.as_slice() == [0xa5, 0xa5, 0x0d, 0x00]);
+
+    // shrinking and growing again within the array clears the added bits
+    bf.truncate_bits(12);
+    assert!(bf.as_slice() == [0xa5, 0xa0]);
+    assert!(bf.push_bits(0x3, 2).is_ok());
+    assert!(bf.bit_len() == 14);
+    assert!(bf.retrieve_u16(0, 13) == Ok(0x296b));
+    assert!(bf.pop_bits(6) == Ok(0x2b));
+    assert!(bf.as_slice() == [0xa5]);
+
+    let mut lsb0 = BitField::from_storage([0u8; 2]).with_bit_order(BitOrder::Lsb0);
+    assert!(lsb0.insert_u64(0xabc, 0, 11).is_ok());
+    assert!(lsb0.as_slice() == [0x0a, 0xbc]);
+    assert!(lsb0 == BitField::from_storage([0x0au8, 0xbc]).with_bit_order(BitOrder::Lsb0));
+
+    let mut borrowed = [0xffu8; 3];
+    {
+        let mut bf = BitField::from_storage(&mut borrowed[..]);
+        bf.truncate_bits(0);
+        assert!(bf.push_bits(0x5, 4).is_ok());
+        assert!(bf.push_bits(0xa5, 8).is_ok());
+        assert!(bf.as_slice() == [0x5a, 0x50]);
+    }
+    assert!(borrowed[..2] == [0x5a, 0x50]);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitfield_capacity_exceeded()
+{
+    let mut bf = BitField::from_storage([0u8; 2]);
+    assert!(bf.bit_len() == 16);
+    assert!(bf.try_resize_bits(17) ==
+            Err(BitFieldError::CapacityExceeded{bits: 17, capacity_bits: 16}));
+    assert!(bf.push_bits(1, 1) ==
+            Err(BitFieldError::CapacityExceeded{bits: 17, capacity_bits: 16}));
+    assert!(bf.bit_len() == 16);
+
+    // the object is left as it was
+    bf.truncate_bits(10);
+    assert!(bf.set_range(0, 9).is_ok());
+    assert!(bf.push_bits(0x5a, 8) ==
+            Err(BitFieldError::CapacityExceeded{bits: 18, capacity_bits: 16}));
+    assert!(bf.bit_len() == 10);
+    assert!(bf.as_slice() == [0xff, 0xc0]);
+    assert!(bf.push_bits(0x2a, 6).is_ok());
+    assert!(bf.as_slice() == [0xff, 0xea]);
+
+    let mut borrowed = [0u8; 1];
+    let mut bf = BitField::from_storage(&mut borrowed[..]).with_bit_order(BitOrder::Lsb0);
+    assert!(bf.try_resize_bits(9) ==
+            Err(BitFieldError::CapacityExceeded{bits: 9, capacity_bits: 8}));
+    assert!(bf.try_resize_bits(4).is_ok());
+    assert!(bf.bit_len() == 4);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+#[should_panic]
+fn bitfield_resize_beyond_capacity()
+{
+    let mut bf = BitField::from_storage([0u8; 2]);
+    bf.resize_bits(17);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitslice_borrowed_bytes()
+{
+    // 0         1         2         3
+    // 01234567890123456789012345678901
+    // 00011010110011111111110000011101
+    let packet = [0x1a, 0xcf, 0xfc, 0x1d];
+    let view = BitSlice::new(&packet);
+
+    assert!(view.bit_len() == 32);
+    assert!(view.retrieve_u64(3, 6) == Ok(0xd));
+
+    let field = view.slice(8..24).unwrap();
+    assert!(field.bit_len() == 16);
+    assert!(field.retrieve_u16(0, 15) == Ok(0xcffc));
+    assert!(field.get_bit(2) == Ok(false));
+    assert!(field.count_ones() == 12);
+    assert!(field.first_set_range(2..) == Ok(Some(4)));
+    assert!(field.retrieve_u64(8, 16) ==
+            Err(BitFieldError::InvalidIndex{start_bit: 8, stop_bit: 16,
+                                            bit_len: 16}));
+
+    let inner = field.slice(4..=11).unwrap();
+    assert!(inner.get::<u8, _>(..) == Ok(0xff));
+    assert!(inner.iter().all(|b| b));
+    assert!(field.find_pattern(inner) == Some(4));
+    assert!(field.slice(..20).is_err());
+
+    #[cfg(feature = "alloc")]
+    {
+        let copy = inner.to_bitfield();
+        assert!(copy.bit_len() == 8 && copy.as_slice() == [0xff]);
+    }
+
+    let lsb0 = BitSlice::new(&packet).with_bit_order(BitOrder::Lsb0);
+    assert!(lsb0.slice(4..12).unwrap().retrieve_u8(0, 7) == Ok(0xc1));
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn bitslice_mut_borrowed_bytes()
+{
+    let mut packet = [0u8; 4];
+    {
+        let mut view = BitSliceMut::new(&mut packet);
+        let mut field = view.slice_mut(4..20).unwrap();
+
+        assert!(field.insert_u64(0xabcd, 0, 15).is_ok());
+        assert!(field.set_bit(16) ==
+                Err(BitFieldError::InvalidIndex{start_bit: 16, stop_bit: 16,
+                                                bit_len: 16}));
+        assert!(field.retrieve_u16(0, 15) == Ok(0xabcd));
+        assert!(field.slice_mut(8..).unwrap().insert_u8(0x12, 0, 7).is_ok());
+        assert!(view.set_range(28, 31).is_ok());
+    }
+    assert!(packet == [0x0a, 0xb1, 0x20, 0x0f]);
+
+    {
+        let mut view = BitSliceMut::new(&mut packet[1..])
+                           .with_overflow_policy(OverflowPolicy::Checked);
+        assert!(view.insert_u64(0x100, 0, 7) == Err(BitFieldError::Overflow{width: 8}));
+        assert!(view.insert_bytes(&[0x01, 0x00], 4, 11) ==
+                Err(BitFieldError::Overflow{width: 8}));
+        assert!(view.insert_bytes(&[0x00, 0x5a], 4, 11).is_ok());
+    }
+    assert!(packet == [0x0a, 0xb5, 0xa0, 0x0f]);
+
+    let mut bf = BitField::from_storage([0x00u8, 0x00]).with_bit_order(BitOrder::Lsb0);
+    assert!(bf.slice_mut(4..12).unwrap().insert_u8(0xa5, 0, 7).is_ok());
+    assert!(bf.as_slice() == [0x0a, 0x50]);
+    assert!(bf.as_bitslice().slice(4..12).unwrap().retrieve_u8(0, 7) == Ok(0xa5));
+}
+
+///////////////////////////////////////////////////////////////////////////////
+#[test]
+fn hex_dump_array_storage()
+{
+    let mut bf = BitField::from_storage([0xa5u8, 0x5a, 0xff, 0x00, 0x81, 0x7e]);
+    bf.truncate_bits(43);
+
+    // the same layout as the Vec backed object's debug_string
+    assert_eq!(HexDump::new(&bf).to_string(),
+               concat!("Offset  Binary                                Hex      \n",
+                       "=======================================================\n",
+                       "000000: 10100101 01011010 11111111 00000000   a55a ff00\n",
+                       "000004: 10000001 011-----                     8160\n"));
+    assert_eq!(bf.hex_dump().xxd().to_string(),
+               "00000000: a55a ff00 8160                           .Z...`\n");
+    assert_eq!(bf.hex_dump()
+                 .with_binary(false)
+                 .with_offset(OffsetBase::Decimal, 4)
+                 .with_bytes_per_row(2)
+                 .with_range(2..6)
+                 .to_string(),
+               concat!("Offset  Hex \n",
+                       "============\n",
+                       "0002:   ff00\n",
+                       "0004:   8160\n"));
+}